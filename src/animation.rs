@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+// Starts fast and settles gently at the end.
+pub fn ease_out_cubic(t: f32) -> f32 {
+    1.0 - (1.0 - t).powi(3)
+}
+
+// Starts and ends slowly, fastest in the middle.
+pub fn ease_in_out(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+// Moves a value from start to end over a duration, advanced by frame time so it
+// runs at the same speed regardless of frame rate.
+#[derive(Debug, Clone)]
+pub struct Tween {
+    pub start: f32,
+    pub end: f32,
+    pub duration: f32,
+    pub elapsed: f32,
+}
+
+impl Tween {
+    pub fn new(start: f32, end: f32, duration: f32) -> Self {
+        Self {
+            start,
+            end,
+            duration,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn advance(&mut self, delta: Duration) {
+        self.elapsed = (self.elapsed + delta.as_secs_f32()).min(self.duration);
+    }
+
+    pub fn restart(&mut self) {
+        self.elapsed = 0.0;
+    }
+
+    pub fn finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    // Fraction of the duration that has passed, between 0 and 1.
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return 1.0;
+        }
+        (self.elapsed / self.duration).clamp(0.0, 1.0)
+    }
+
+    pub fn value(&self) -> f32 {
+        self.start + (self.end - self.start) * self.progress()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn easing_curves_hit_their_fixed_points() {
+        assert_eq!(ease_out_cubic(0.0), 0.0);
+        assert_eq!(ease_out_cubic(0.5), 0.875);
+        assert_eq!(ease_out_cubic(1.0), 1.0);
+
+        assert_eq!(ease_in_out(0.0), 0.0);
+        assert_eq!(ease_in_out(0.5), 0.5);
+        assert_eq!(ease_in_out(1.0), 1.0);
+    }
+
+    #[test]
+    fn tween_stops_at_its_end() {
+        let mut tween = Tween::new(10.0, 20.0, 2.0);
+        tween.advance(Duration::from_secs(1));
+        assert_eq!(tween.progress(), 0.5);
+        assert_eq!(tween.value(), 15.0);
+        assert!(!tween.finished());
+
+        tween.advance(Duration::from_secs(5));
+        assert_eq!(tween.elapsed, 2.0);
+        assert_eq!(tween.progress(), 1.0);
+        assert_eq!(tween.value(), 20.0);
+        assert!(tween.finished());
+    }
+
+    #[test]
+    fn zero_length_tween_is_already_at_its_end() {
+        let mut tween = Tween::new(1.0, 3.0, 0.0);
+        assert_eq!(tween.progress(), 1.0);
+        assert_eq!(tween.value(), 3.0);
+        tween.advance(Duration::from_secs(1));
+        assert_eq!(tween.elapsed, 0.0);
+        assert!(tween.finished());
+    }
+}
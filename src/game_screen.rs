@@ -1,4 +1,7 @@
+use crate::animation::{ease_out_cubic, Tween};
+use crate::input::{KeyAction, KeyBindings};
 use crate::level::*;
+use crate::transition::ScreenTransition;
 use crate::AppState;
 use crate::GameState;
 use crate::GlobalVolumeSettings;
@@ -12,13 +15,21 @@ use bevy::prelude::*;
 use bevy::sprite::*;
 use bevy::window::PrimaryWindow;
 use rand::prelude::*;
+use std::collections::VecDeque;
 use std::default::Default;
+use std::time::Duration;
 
 pub struct GameScreenPlugin<S: States + Copy>(pub S);
 
 impl<S: States + Copy> Plugin for GameScreenPlugin<S> {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(self.0), create_game_screen)
+        app.add_event::<GameAction>()
+            .add_event::<InvalidAttempt>()
+            .init_resource::<InvalidAttemptFeedback>()
+            .init_resource::<SolutionReveal>()
+            .init_resource::<ActionLog>()
+            .init_resource::<HoveredCell>()
+            .add_systems(OnEnter(self.0), create_game_screen)
             .add_systems(
                 Update,
                 (
@@ -29,6 +40,16 @@ impl<S: States + Copy> Plugin for GameScreenPlugin<S> {
                     update_cell_hints,
                     detect_complete_level,
                     handle_mouse_input,
+                    start_invalid_attempt_feedback,
+                    advance_solution_reveal,
+                    update_hovered_cell,
+                    update_cell_highlight,
+                    update_coordinate_labels,
+                    update_action_log,
+                    update_dead_end_warning,
+                    update_violation_list,
+                    update_coverage,
+                    inspect_cell,
                     button_system,
                     // ui_apply_fixed_z
                 )
@@ -63,6 +84,14 @@ enum GameScreenButtonAction {
 
 pub const CELL_SIZE: f32 = 150.0;
 
+// Space reserved at the top of the window for the HUD buttons and level name.
+pub const HUD_HEIGHT: f32 = 130.0;
+pub const BOARD_MARGIN: f32 = 40.0;
+
+// How long the board takes to slide and fade in after a level is loaded.
+pub const LEVEL_TRANSITION_SECONDS: f32 = 0.5;
+pub const LEVEL_TRANSITION_OFFSET: f32 = 200.0;
+
 pub const GRASS_LAYER: f32 = 0.0;
 pub const MARKER_LAYER: f32 = 100.0;
 pub const CELL_LAYER: f32 = 200.0;
@@ -70,8 +99,28 @@ pub const CROSS_LAYER: f32 = 300.0;
 pub const TEXT_LAYER: f32 = 400.0;
 pub const AXIS_LAYER: f32 = 500.0;
 
-// Update if the size of the field grows beyond 10x10.
-pub const MAX_HOUSE_COUNT: usize = 100;
+// How long and how far the board shakes after an invalid placement attempt.
+pub const INVALID_ATTEMPT_SECONDS: f32 = 0.3;
+pub const INVALID_ATTEMPT_SHAKE: f32 = 12.0;
+
+// Delay between houses when the solution is revealed one by one.
+pub const REVEAL_STEP_SECONDS: f32 = 0.3;
+
+// How many misplaced houses the violation list shows unless all are toggled on.
+pub const FIRST_VIOLATIONS_SHOWN: usize = 3;
+
+// How many recent actions the on-screen log keeps.
+pub const ACTION_LOG_SIZE: usize = 6;
+
+// A house can stand on at most every cell of the field.
+pub const MAX_HOUSE_COUNT: usize = MAX_CELLS;
+
+// Multiplied into the grass sprite under the cursor.
+const HOVER_TINT: Color = Color::rgb(1.0, 1.0, 0.7);
+// Multiplied into the grass sprite of a cell where placement was refused.
+const INVALID_ATTEMPT_TINT: Color = Color::rgb(1.0, 0.5, 0.5);
+// Multiplied into grass sprites where a house could go, when that aid is on.
+const PLACEABLE_TINT: Color = Color::rgb(0.75, 1.0, 0.75);
 
 const NORMAL_BUTTON: Color = Color::WHITE;
 const HOVERED_BUTTON: Color = Color::rgb(0.8, 0.8, 0.8);
@@ -82,9 +131,174 @@ pub struct GameScreenRoot {
     random_number: Vec<Vec<u32>>,
 }
 
+#[derive(Component)]
+pub struct LevelTransition {
+    tween: Tween,
+}
+
+impl Default for LevelTransition {
+    fn default() -> Self {
+        Self {
+            tween: Tween::new(0.0, 1.0, LEVEL_TRANSITION_SECONDS),
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct SolutionStatusText;
 
+#[derive(Component)]
+pub struct ActionLogText;
+
+#[derive(Component)]
+pub struct GrassCell {
+    row: usize,
+    col: usize,
+}
+
+#[derive(Component)]
+pub struct CoordinateLabel;
+
+#[derive(Component)]
+pub struct DeadEndText;
+
+#[derive(Component)]
+pub struct CellInspectorText;
+
+#[derive(Component)]
+pub struct ViolationListText;
+
+#[derive(Component)]
+pub struct CoverageText;
+
+// The board cell under the mouse cursor, if any.
+#[derive(Resource, Default)]
+pub struct HoveredCell {
+    pub position: Option<Position>,
+}
+
+// Something the player did on the board.
+#[derive(Event, Debug, Clone, Copy)]
+pub enum GameAction {
+    PlaceHouse(Position),
+    RemoveHouse(Position),
+    Mark(Position),
+    Unmark(Position),
+    ClearHouses,
+    ResetBoard,
+    Solved,
+}
+
+impl GameAction {
+    // Names the bound keys for follow-up actions, so the text matches custom bindings.
+    pub fn describe(&self, bindings: &KeyBindings) -> String {
+        match self {
+            GameAction::PlaceHouse(p) => format!("Placed house at {}", coordinate_label(*p)),
+            GameAction::RemoveHouse(p) => format!("Removed house at {}", coordinate_label(*p)),
+            GameAction::Mark(p) => format!("Marked {}", coordinate_label(*p)),
+            GameAction::Unmark(p) => format!("Unmarked {}", coordinate_label(*p)),
+            GameAction::ClearHouses => "Cleared all houses".to_string(),
+            GameAction::ResetBoard => "Reset the board".to_string(),
+            GameAction::Solved => match bindings
+                .keys
+                .get(&KeyAction::KeepEditing)
+                .and_then(|keys| keys.first())
+            {
+                Some(key) => format!("Solution valid! Press {:?} to keep editing", key),
+                None => "Solution valid!".to_string(),
+            },
+        }
+    }
+}
+
+// A click that tried to place a house where it can't go.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct InvalidAttempt(pub Position);
+
+// Drives the board shake and cell flash for the latest invalid attempt.
+#[derive(Resource)]
+pub struct InvalidAttemptFeedback {
+    position: Option<Position>,
+    // Shake amplitude, decaying to zero.
+    shake: Tween,
+}
+
+impl Default for InvalidAttemptFeedback {
+    fn default() -> Self {
+        Self {
+            position: None,
+            shake: Tween::new(INVALID_ATTEMPT_SHAKE, 0.0, INVALID_ATTEMPT_SECONDS),
+        }
+    }
+}
+
+// Houses of the solution still to be shown, placed one per REVEAL_STEP_SECONDS.
+#[derive(Resource)]
+pub struct SolutionReveal {
+    pending: VecDeque<Position>,
+    timer: Timer,
+}
+
+impl Default for SolutionReveal {
+    fn default() -> Self {
+        Self {
+            pending: VecDeque::new(),
+            timer: Timer::from_seconds(REVEAL_STEP_SECONDS, TimerMode::Repeating),
+        }
+    }
+}
+
+impl SolutionReveal {
+    pub fn start(&mut self, solution: &Solution) {
+        self.pending = solution.placements.iter().map(|x| x.position).collect();
+        self.timer.reset();
+    }
+
+    pub fn cancel(&mut self) {
+        self.pending.clear();
+    }
+
+    // Advances the reveal by the elapsed time and returns the houses due to be placed.
+    // Houses already in the solution are skipped without using up a step.
+    pub fn advance(&mut self, delta: Duration, solution: &Solution) -> Vec<Position> {
+        if self.pending.is_empty() {
+            return Vec::new();
+        }
+        self.timer.tick(delta);
+        let placed = |position: &Position| {
+            solution
+                .placements
+                .iter()
+                .any(|placement| placement.position == *position)
+        };
+        let mut due = Vec::new();
+        for _ in 0..self.timer.times_finished_this_tick() {
+            let Some(position) =
+                std::iter::from_fn(|| self.pending.pop_front()).find(|position| !placed(position))
+            else {
+                break;
+            };
+            due.push(position);
+        }
+        due
+    }
+}
+
+// The most recent actions, oldest first.
+#[derive(Resource, Default)]
+pub struct ActionLog {
+    entries: VecDeque<String>,
+}
+
+impl ActionLog {
+    pub fn push(&mut self, entry: String) {
+        if self.entries.len() == ACTION_LOG_SIZE {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+}
+
 #[derive(Component)]
 pub struct RowBuildingsRequired {
     row: usize,
@@ -150,16 +364,19 @@ pub fn item_cell(
     } else {
         server.load(format!("grass_iso_light_{rid}.png"))
     };
-    builder.spawn(SpriteBundle {
-        sprite: Sprite {
-            custom_size: Some(Vec2::new(CELL_SIZE, CELL_SIZE)),
-            anchor: Anchor::CenterLeft,
+    builder.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(Vec2::new(CELL_SIZE, CELL_SIZE)),
+                anchor: Anchor::CenterLeft,
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(ix, iy, z + GRASS_LAYER),
+            texture: grass_texture,
             ..Default::default()
         },
-        transform: Transform::from_xyz(ix, iy, z + GRASS_LAYER),
-        texture: grass_texture,
-        ..Default::default()
-    });
+        GrassCell { row: r, col: c },
+    ));
 
     if cell_type != CellType::Grass {
         builder.spawn(SpriteBundle {
@@ -272,7 +489,7 @@ pub fn item_number_constraints(
     }
 }
 
-pub fn create_hud(commands: &mut Commands, name: &str, server: &Res<AssetServer>) {
+pub fn create_hud(commands: &mut Commands, name: &str, puzzle: &Puzzle, server: &Res<AssetServer>) {
     commands
         .spawn((
             NodeBundle {
@@ -362,6 +579,134 @@ pub fn create_hud(commands: &mut Commands, name: &str, server: &Res<AssetServer>
                 GameScreenButtonAction::Complete,
             ));
         });
+
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: server.load("NotoSerif-SemiBold.ttf"),
+                font_size: 20.0,
+                color: Color::WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(20.0),
+            bottom: Val::Px(20.0),
+            ..default()
+        }),
+        ActionLogText,
+        OnGameScreen,
+    ));
+
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: server.load("NotoSerif-SemiBold.ttf"),
+                font_size: 20.0,
+                color: Color::WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(20.0),
+            top: Val::Px(HUD_HEIGHT),
+            ..default()
+        }),
+        CellInspectorText,
+        OnGameScreen,
+    ));
+
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: server.load("NotoSerif-SemiBold.ttf"),
+                font_size: 20.0,
+                color: Color::WHITE,
+            },
+        )
+        .with_text_alignment(TextAlignment::Right)
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            right: Val::Px(20.0),
+            top: Val::Px(HUD_HEIGHT),
+            ..default()
+        }),
+        ViolationListText,
+        OnGameScreen,
+    ));
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    // Below the tutorial prompt, which sits right under the HUD.
+                    top: Val::Px(HUD_HEIGHT + 50.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            OnGameScreen,
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: server.load(crate::TEXT_FONT_NAME),
+                        font_size: 48.0,
+                        color: crate::CUSTOM_ORANGE,
+                    },
+                ),
+                DeadEndText,
+            ));
+        });
+
+    commands.spawn((
+        TextBundle::from_section(
+            active_rules(puzzle).join("\n"),
+            TextStyle {
+                font: server.load("NotoSerif-SemiBold.ttf"),
+                font_size: 20.0,
+                color: Color::WHITE,
+            },
+        )
+        .with_text_alignment(TextAlignment::Right)
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            right: Val::Px(20.0),
+            bottom: Val::Px(20.0),
+            ..default()
+        }),
+        OnGameScreen,
+    ));
+
+    // The sandbox doubles as a level editor, so it shows how full the layout is.
+    if puzzle.sandbox {
+        commands.spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: server.load("NotoSerif-SemiBold.ttf"),
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                right: Val::Px(20.0),
+                bottom: Val::Px(50.0),
+                ..default()
+            }),
+            CoverageText,
+            OnGameScreen,
+        ));
+    }
 }
 
 #[derive(Component)]
@@ -373,10 +718,12 @@ pub fn create_game_screen(
     server: Res<AssetServer>,
 ) {
     let game_screen_entity = commands.spawn(SpatialBundle::default()).id();
+    commands.insert_resource(ActionLog::default());
+    commands.insert_resource(SolutionReveal::default());
     // This component is added to the entity in the end of this function.
     let mut game_screen_root = GameScreenRoot::default();
 
-    create_hud(&mut commands, &game_state.name, &server);
+    create_hud(&mut commands, &game_state.name, &game_state.puzzle, &server);
 
     let puzzle = &game_state.puzzle;
     let (rows, cols) = puzzle.dims();
@@ -420,13 +767,17 @@ pub fn create_game_screen(
             }
         });
 
+    if !puzzle.sandbox {
+        commands
+            .entity(game_screen_entity)
+            .with_children(|builder| {
+                item_number_constraints(builder, &puzzle, &server);
+            });
+    }
+
     commands
         .entity(game_screen_entity)
-        .with_children(|builder| {
-            item_number_constraints(builder, &puzzle, &server);
-        });
-
-    commands.entity(game_screen_entity).insert(game_screen_root);
+        .insert((game_screen_root, LevelTransition::default()));
 
     // let ambient_id = commands.spawn((
     //     AudioBundle {
@@ -458,26 +809,132 @@ pub fn destroy_game_screen(
     }
 }
 
+// Size of the isometric board in world units.
+pub fn board_size(rows: usize, cols: usize) -> Vec2 {
+    let diagonal = (rows + cols) as f32;
+    Vec2::new(
+        diagonal * CELL_SIZE * 0.5,
+        diagonal * CELL_SIZE * 0.25 + CELL_SIZE * 0.5,
+    )
+}
+
+// Center of the isometric board relative to the game screen root.
+pub fn board_center(rows: usize, cols: usize) -> Vec2 {
+    Vec2::new(
+        (rows + cols) as f32 * CELL_SIZE * 0.25,
+        (cols as f32 - rows as f32) * CELL_SIZE * 0.125,
+    )
+}
+
+// Camera scale that fits the board into the play area below the HUD. Never zooms in.
+pub fn fit_scale(board: Vec2, window: Vec2) -> f32 {
+    let available = Vec2::new(
+        window.x - 2.0 * BOARD_MARGIN,
+        window.y - HUD_HEIGHT - 2.0 * BOARD_MARGIN,
+    )
+    .max(Vec2::ONE);
+    (board / available).max_element().max(1.0)
+}
+
 pub fn update_game_screen(
+    time: Res<Time>,
     game_state: Res<GameState>,
-    mut game_screen_query: Query<(Entity, &GameScreenRoot, &mut Transform)>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut projection_query: Query<&mut OrthographicProjection>,
+    mut game_screen_query: Query<
+        (&mut Transform, &mut LevelTransition, &Children),
+        With<GameScreenRoot>,
+    >,
+    mut sprites_query: Query<&mut Sprite>,
+    mut feedback: ResMut<InvalidAttemptFeedback>,
 ) {
-    let (_, _, mut transform) = game_screen_query.single_mut();
-    let puzzle = &game_state.puzzle;
-    let (rows, cols) = puzzle.dims();
-    let (puzzle_width, _puzzle_height) = (cols as f32 * CELL_SIZE, rows as f32 * CELL_SIZE);
-    transform.translation = Vec3::new(-puzzle_width / 2.0, 0.0, 0.0);
+    let (mut transform, mut transition, children) = game_screen_query.single_mut();
+    let transition_running = !transition.tween.finished();
+    transition.tween.advance(time.delta());
+    // Ease out, so the board settles gently into place.
+    let progress = ease_out_cubic(transition.tween.value());
+
+    let window = window_query.single();
+    let (rows, cols) = game_state.puzzle.dims();
+    let scale = fit_scale(
+        board_size(rows, cols),
+        Vec2::new(window.width(), window.height()),
+    );
+    let mut projection = projection_query.single_mut();
+    if projection.scale != scale {
+        projection.scale = scale;
+    }
+
+    // Keep the board centered in the play area, which is the window minus the HUD.
+    let center = board_center(rows, cols);
+    let offset = (1.0 - progress) * LEVEL_TRANSITION_OFFSET;
+
+    // Shake sideways while the invalid attempt feedback runs, fading out.
+    let mut shake = 0.0;
+    if feedback.position.is_some() {
+        feedback.shake.advance(time.delta());
+        if feedback.shake.finished() {
+            feedback.position = None;
+        } else {
+            shake = (feedback.shake.elapsed * 60.0).sin() * feedback.shake.value();
+        }
+    }
+
+    transform.translation = Vec3::new(
+        -center.x + shake,
+        -center.y - HUD_HEIGHT * 0.5 * scale - offset,
+        0.0,
+    );
+
+    // Only the board fades in; the previous board was already faded out by the
+    // screen transition before this one was built.
+    if transition_running {
+        for &child in children.iter() {
+            if let Ok(mut sprite) = sprites_query.get_mut(child) {
+                sprite.color.set_a(progress);
+            }
+        }
+    }
+}
+
+// Indices of the house sprites whose placement differs between the two lists,
+// including houses added or removed at the end.
+pub fn changed_house_indices(old: &[Position], new: &[Position]) -> Vec<usize> {
+    (0..old.len().max(new.len()))
+        .filter(|&index| old.get(index) != new.get(index))
+        .collect()
 }
 
 pub fn update_placements_render(
     game_state: Res<GameState>,
     mut houses_query: Query<(&mut Transform, &mut Visibility, &HouseIndex)>,
+    new_houses_query: Query<(), Added<HouseIndex>>,
+    // Placements as of the last update, to find the houses that moved.
+    mut rendered: Local<Vec<Position>>,
 ) {
+    // Houses only move when the solution changes or the board is rebuilt.
+    if !game_state.is_changed() && new_houses_query.is_empty() {
+        return;
+    }
+    // A rebuilt board starts with every house hidden, so all of them are refreshed.
+    if !new_houses_query.is_empty() {
+        rendered.clear();
+    }
+
+    let positions: Vec<Position> = game_state
+        .solution
+        .placements
+        .iter()
+        .map(|placement| placement.position)
+        .collect();
+    let changed = changed_house_indices(&rendered, &positions);
     let (_rows, cols) = game_state.puzzle.dims();
     for (mut transform, mut visibility, house_index) in houses_query.iter_mut() {
-        if house_index.index < game_state.solution.placements.len() {
-            let position = game_state.solution.placements[house_index.index].position;
-            *visibility = Visibility::Inherited;
+        if !changed.contains(&house_index.index) {
+            continue;
+        }
+        if let Some(&position) = positions.get(house_index.index) {
+            visibility.set_if_neq(Visibility::Inherited);
 
             let (c, r) = (position.col, position.row);
             let ix = (c as f32 + r as f32) * CELL_SIZE * 0.5;
@@ -485,11 +942,12 @@ pub fn update_placements_render(
 
             let z = ((cols - c + 1) + r) as f32 * 0.1;
 
-            *transform = Transform::from_xyz(ix, iy, z + CELL_LAYER);
+            transform.set_if_neq(Transform::from_xyz(ix, iy, z + CELL_LAYER));
         } else {
-            *visibility = Visibility::Hidden;
+            visibility.set_if_neq(Visibility::Hidden);
         }
     }
+    *rendered = positions;
 }
 
 pub fn update_buildings_required(
@@ -502,7 +960,11 @@ pub fn update_buildings_required(
         (&mut Text, &ColBuildingsRequired),
         Without<RowBuildingsRequired>,
     >,
+    new_text_query: Query<(), Added<RowBuildingsRequired>>,
 ) {
+    if !game_state.is_changed() && new_text_query.is_empty() {
+        return;
+    }
     let validation_result = validate_solution(&game_state.solution, &game_state.puzzle);
     let (rows, cols) = game_state.puzzle.dims();
 
@@ -543,7 +1005,11 @@ pub fn update_incorrect_placements(
     game_state: Res<GameState>,
     mut incorrect_placements_query: Query<(&mut Visibility, &IncorrectPlacement)>,
     mut constraint_violations_query: Query<(&mut Text, &ConstraintViolationRender)>,
+    new_render_query: Query<(), Added<IncorrectPlacement>>,
 ) {
+    if !game_state.is_changed() && new_render_query.is_empty() {
+        return;
+    }
     let validation_result = validate_solution(&game_state.solution, &game_state.puzzle);
     let (rows, cols) = game_state.puzzle.dims();
 
@@ -614,29 +1080,77 @@ pub fn update_cell_hints(
 
 fn detect_complete_level(
     mut commands: Commands,
-    game_state: Res<GameState>,
+    mut game_state: ResMut<GameState>,
     mut complete_banner: Query<&mut Visibility, With<CompleteBanner>>,
+    new_banner_query: Query<(), Added<CompleteBanner>>,
+    mut actions: EventWriter<GameAction>,
     server: Res<AssetServer>,
 ) {
+    if !game_state.is_changed() && new_banner_query.is_empty() {
+        return;
+    }
     let validation_result = validate_solution(&game_state.solution, &game_state.puzzle);
-    if validation_result.complete {
-        let mut visibility = complete_banner.get_single_mut().unwrap();
-        if matches!(*visibility, Visibility::Hidden) {
-            *visibility = Visibility::Visible;
-            commands.spawn((
-                AudioBundle {
-                    source: server.load("level_success.wav"),
-                    settings: PlaybackSettings {
-                        mode: PlaybackMode::Despawn,
-                        volume: Volume::new_absolute(0.0),
-                        speed: 1.2,
-                        ..default()
-                    },
+    let mut visibility = complete_banner.get_single_mut().unwrap();
+    if !validation_result.complete {
+        // The board was edited or reset after solving.
+        visibility.set_if_neq(Visibility::Hidden);
+    } else if matches!(*visibility, Visibility::Hidden) {
+        *visibility = Visibility::Visible;
+        if !game_state.revealed {
+            let id = game_state.puzzle.id();
+            game_state.solved_levels.insert(id);
+        }
+        game_state.locked = true;
+        info!(
+            "Solved {}:\n{}",
+            game_state.name,
+            render_ascii(&game_state.puzzle, &game_state.solution)
+        );
+        actions.send(GameAction::Solved);
+        commands.spawn((
+            AudioBundle {
+                source: server.load("level_success.wav"),
+                settings: PlaybackSettings {
+                    mode: PlaybackMode::Despawn,
+                    volume: Volume::new_absolute(0.0),
+                    speed: 1.2,
                     ..default()
                 },
-                VolumeSettings { volume: 0.4 },
-            ));
-        }
+                ..default()
+            },
+            VolumeSettings { volume: 0.4 },
+        ));
+    }
+}
+
+// Converts a cursor position in window coordinates to the board cell under it.
+pub fn cursor_to_cell(
+    cursor: Vec2,
+    camera: &Camera,
+    camera_global_transform: &GlobalTransform,
+    game_screen_transform: &Transform,
+    puzzle: &Puzzle,
+) -> Option<Position> {
+    let point = camera.viewport_to_world_2d(camera_global_transform, cursor)?
+        - game_screen_transform.translation.xy();
+    let (rows, cols) = puzzle.dims();
+    board_point_to_cell(point, rows, cols)
+}
+
+// Finds the cell at a point relative to the game screen root, if it is on the board.
+pub fn board_point_to_cell(point: Vec2, rows: usize, cols: usize) -> Option<Position> {
+    // Undo the isometric projection used by item_cell.
+    let p = Vec2::new(point.x + 2.0 * point.y, point.x - 2.0 * point.y) / CELL_SIZE;
+
+    let lower_bound = Vec2::new(0.0, 0.0);
+    let upper_bound = Vec2::new(cols as f32, rows as f32);
+    if p.cmpge(lower_bound).all() && p.cmplt(upper_bound).all() {
+        Some(Position {
+            row: p.y as usize,
+            col: p.x as usize,
+        })
+    } else {
+        None
     }
 }
 
@@ -647,50 +1161,70 @@ fn handle_mouse_input(
     game_screen_query: Query<&Transform, With<GameScreenRoot>>,
     camera_query: Query<(&Camera, &GlobalTransform)>,
     mut game_state: ResMut<GameState>,
+    mut actions: EventWriter<GameAction>,
+    mut invalid_attempts: EventWriter<InvalidAttempt>,
+    mut reveal: ResMut<SolutionReveal>,
+    keys: Res<Input<KeyCode>>,
+    bindings: Res<KeyBindings>,
     mut commands: Commands,
     server: Res<AssetServer>,
 ) {
+    // Clicks while inspecting only select the cell to describe.
+    if cfg!(debug_assertions) && bindings.pressed(&keys, KeyAction::InspectCell) {
+        return;
+    }
     let game_screen_transform = game_screen_query.single();
     let (camera, camera_global_transform) = camera_query.single();
     let window = window_query.single();
-    let (rows, cols) = game_state.puzzle.dims();
 
     let left_just_pressed =
         mouse.just_pressed(MouseButton::Left) || touches_input.any_just_pressed();
     let right_just_pressed = mouse.just_pressed(MouseButton::Right);
+    if game_state.locked {
+        return;
+    }
+    // Clicking takes over from a running solution reveal.
+    if (left_just_pressed || right_just_pressed) && !reveal.pending.is_empty() {
+        reveal.cancel();
+    }
 
-    let isometric_to_orthographic = |pi: Vec2| {
-        let pi = pi - game_screen_transform.translation.xy();
-        let po = Vec2::new(pi.x + 2.0 * pi.y, pi.x - 2.0 * pi.y);
-        po / CELL_SIZE
-    };
-
-    if let Some(p) = window
+    if let Some(position) = window
         .cursor_position()
         .or_else(|| touches_input.first_pressed_position())
-        .and_then(|cursor| camera.viewport_to_world_2d(camera_global_transform, cursor))
-        .map(isometric_to_orthographic)
+        .and_then(|cursor| {
+            cursor_to_cell(
+                cursor,
+                camera,
+                camera_global_transform,
+                game_screen_transform,
+                &game_state.puzzle,
+            )
+        })
     {
-        let lower_bound = Vec2::new(0.0, 0.0);
-        let upper_bound = Vec2::new(cols as f32, rows as f32);
-        if p.cmpge(lower_bound).all() && p.cmplt(upper_bound).all() {
-            let position = Position {
-                row: p.y as usize,
-                col: p.x as usize,
-            };
-            let r = position.row;
-            let c = position.col;
+        let r = position.row;
+        let c = position.col;
+        let button = if left_just_pressed {
+            MouseButton::Left
+        } else if right_just_pressed {
+            MouseButton::Right
+        } else {
+            return;
+        };
 
-            if left_just_pressed
-                && game_state.puzzle.field[r][c] == CellType::Grass
-                && game_state
+        match click_outcome(
+            &game_state.puzzle,
+            &game_state.solution,
+            game_state.strict_budget,
+            position,
+            button,
+        ) {
+            ClickOutcome::Place => {
+                game_state
                     .solution
                     .placements
-                    .iter()
-                    .all(|x| !(x.position == position))
-            {
-                game_state.solution.placements.push(Placement { position });
+                    .push(Placement::new(position));
                 game_state.hints[r][c] = false;
+                actions.send(GameAction::PlaceHouse(position));
 
                 commands.spawn((
                     AudioBundle {
@@ -705,54 +1239,342 @@ fn handle_mouse_input(
                     },
                     VolumeSettings { volume: 0.6 },
                 ));
-            } else if right_just_pressed || left_just_pressed {
-                // Remove placements at this position.
-                if let Some(index) = game_state
-                    .solution
-                    .placements
-                    .iter()
-                    .position(|x| x.position == position)
-                {
-                    game_state.solution.placements.remove(index);
-                    commands.spawn((
-                        AudioBundle {
-                            source: server.load("remove.wav"),
-                            settings: PlaybackSettings {
-                                mode: PlaybackMode::Despawn,
-                                volume: Volume::new_absolute(0.0),
-                                speed: 1.2,
-                                ..default()
-                            },
+            }
+            ClickOutcome::Remove => {
+                game_state.remove_house(position);
+                actions.send(GameAction::RemoveHouse(position));
+                commands.spawn((
+                    AudioBundle {
+                        source: server.load("remove.wav"),
+                        settings: PlaybackSettings {
+                            mode: PlaybackMode::Despawn,
+                            volume: Volume::new_absolute(0.0),
+                            speed: 1.2,
                             ..default()
                         },
-                        VolumeSettings { volume: 0.5 },
-                    ));
-                    game_state.hints[r][c] = false;
-                } else if game_state.puzzle.field[r][c] == CellType::Grass {
-                    let source = if game_state.hints[r][c] {
-                        server.load("erase.wav")
-                    } else {
-                        server.load("draw.wav")
-                    };
-
-                    commands.spawn((
-                        AudioBundle {
-                            source,
-                            settings: PlaybackSettings {
-                                mode: PlaybackMode::Despawn,
-                                volume: Volume::new_absolute(0.0),
-                                speed: 0.9,
-                                ..default()
-                            },
+                        ..default()
+                    },
+                    VolumeSettings { volume: 0.5 },
+                ));
+            }
+            ClickOutcome::ToggleMark => {
+                let source = if game_state.hints[r][c] {
+                    server.load("erase.wav")
+                } else {
+                    server.load("draw.wav")
+                };
+
+                commands.spawn((
+                    AudioBundle {
+                        source,
+                        settings: PlaybackSettings {
+                            mode: PlaybackMode::Despawn,
+                            volume: Volume::new_absolute(0.0),
+                            speed: 0.9,
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    VolumeSettings { volume: 0.12 },
+                ));
+                game_state.hints[r][c] ^= true;
+                actions.send(if game_state.hints[r][c] {
+                    GameAction::Mark(position)
+                } else {
+                    GameAction::Unmark(position)
+                });
+            }
+            ClickOutcome::Invalid => invalid_attempts.send(InvalidAttempt(position)),
+            ClickOutcome::Ignore => {}
+        }
+    }
+}
+
+// What a click on a board cell does.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ClickOutcome {
+    Place,
+    Remove,
+    ToggleMark,
+    // A house can't go there, which the board shows with a shake.
+    Invalid,
+    Ignore,
+}
+
+// A left click builds on free grass, or removes the house already there. Either
+// button removes houses and marks empty grass; with a strict budget, houses that
+// would overfill a row or column are refused.
+pub fn click_outcome(
+    puzzle: &Puzzle,
+    solution: &Solution,
+    strict_budget: bool,
+    position: Position,
+    button: MouseButton,
+) -> ClickOutcome {
+    let occupied = solution
+        .placements
+        .iter()
+        .any(|placement| placement.position == position);
+    let left = button == MouseButton::Left;
+
+    if left && !occupied && Placement::try_place(puzzle, position).is_ok() {
+        if !strict_budget || within_budget(puzzle, solution, position) {
+            ClickOutcome::Place
+        } else {
+            ClickOutcome::Invalid
+        }
+    } else if occupied {
+        ClickOutcome::Remove
+    } else if puzzle.field[position.row][position.col] == CellType::Grass {
+        ClickOutcome::ToggleMark
+    } else if left {
+        ClickOutcome::Invalid
+    } else {
+        ClickOutcome::Ignore
+    }
+}
+
+fn advance_solution_reveal(
+    time: Res<Time>,
+    mut reveal: ResMut<SolutionReveal>,
+    mut game_state: ResMut<GameState>,
+    mut actions: EventWriter<GameAction>,
+) {
+    for position in reveal.advance(time.delta(), &game_state.solution) {
+        game_state.hints[position.row][position.col] = false;
+        game_state
+            .solution
+            .placements
+            .push(Placement::new(position));
+        actions.send(GameAction::PlaceHouse(position));
+    }
+}
+
+fn start_invalid_attempt_feedback(
+    mut invalid_attempts: EventReader<InvalidAttempt>,
+    mut feedback: ResMut<InvalidAttemptFeedback>,
+) {
+    if let Some(InvalidAttempt(position)) = invalid_attempts.iter().last() {
+        feedback.position = Some(*position);
+        feedback.shake.restart();
+    }
+}
+
+fn update_hovered_cell(
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    game_screen_query: Query<&Transform, With<GameScreenRoot>>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    game_state: Res<GameState>,
+    mut hovered_cell: ResMut<HoveredCell>,
+) {
+    let game_screen_transform = game_screen_query.single();
+    let (camera, camera_global_transform) = camera_query.single();
+    let position = window_query.single().cursor_position().and_then(|cursor| {
+        cursor_to_cell(
+            cursor,
+            camera,
+            camera_global_transform,
+            game_screen_transform,
+            &game_state.puzzle,
+        )
+    });
+    if hovered_cell.position != position {
+        hovered_cell.position = position;
+    }
+}
+
+fn update_cell_highlight(
+    hovered_cell: Res<HoveredCell>,
+    feedback: Res<InvalidAttemptFeedback>,
+    game_state: Res<GameState>,
+    mut grass_query: Query<(&mut Sprite, &GrassCell)>,
+    new_grass_query: Query<(), Added<GrassCell>>,
+) {
+    if !hovered_cell.is_changed()
+        && !feedback.is_changed()
+        && !game_state.is_changed()
+        && new_grass_query.is_empty()
+    {
+        return;
+    }
+    let placeable = placeable_cells(&game_state.puzzle, &game_state.solution);
+    for (mut sprite, grass_cell) in grass_query.iter_mut() {
+        let (row, col) = (grass_cell.row, grass_cell.col);
+        let tint = if feedback.position == Some(Position { row, col }) {
+            INVALID_ATTEMPT_TINT
+        } else if hovered_cell.position == Some(Position { row, col }) {
+            HOVER_TINT
+        } else if game_state.show_placeable && placeable[row][col] {
+            PLACEABLE_TINT
+        } else {
+            Color::WHITE
+        };
+        // Keep the alpha, which the level transition animates.
+        let alpha = sprite.color.a();
+        sprite.color = tint.with_a(alpha);
+    }
+}
+
+pub fn coordinate_label(position: Position) -> String {
+    format!("({}, {})", position.row, position.col)
+}
+
+// Spawns the labels when they are turned on and despawns them when turned off.
+fn update_coordinate_labels(
+    mut commands: Commands,
+    game_state: Res<GameState>,
+    server: Res<AssetServer>,
+    game_screen_query: Query<Entity, With<GameScreenRoot>>,
+    labels_query: Query<Entity, With<CoordinateLabel>>,
+) {
+    let shown = !labels_query.is_empty();
+    if game_state.show_coordinates == shown {
+        return;
+    }
+    if !game_state.show_coordinates {
+        for entity in labels_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+
+    let text_style = TextStyle {
+        font: server.load("NotoSerif-SemiBold.ttf"),
+        font_size: 20.0,
+        color: Color::WHITE,
+    };
+    let (rows, cols) = game_state.puzzle.dims();
+    commands
+        .entity(game_screen_query.single())
+        .with_children(|builder| {
+            for r in 0..rows {
+                for c in 0..cols {
+                    let z = ((cols - c + 1) + r) as f32 * 0.1;
+                    let ix = (c as f32 + r as f32) * CELL_SIZE * 0.5;
+                    let iy = (c as f32 - r as f32) * CELL_SIZE * 0.25;
+                    let label = coordinate_label(Position { row: r, col: c });
+                    builder.spawn((
+                        Text2dBundle {
+                            text: Text::from_section(label, text_style.clone())
+                                .with_alignment(TextAlignment::Center),
+                            transform: Transform::from_xyz(
+                                ix + 0.5 * CELL_SIZE,
+                                iy - 0.15 * CELL_SIZE,
+                                z + TEXT_LAYER,
+                            ),
                             ..default()
                         },
-                        VolumeSettings { volume: 0.12 },
+                        CoordinateLabel,
                     ));
-                    game_state.hints[r][c] ^= true;
                 }
             }
-        }
+        });
+}
+
+// Clicking a cell while holding the inspect key shows its debug summary, kept up
+// to date as the board changes. Only in debug builds, like the level audit.
+fn inspect_cell(
+    mouse: Res<Input<MouseButton>>,
+    keys: Res<Input<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    hovered_cell: Res<HoveredCell>,
+    game_state: Res<GameState>,
+    mut inspected: Local<Option<Position>>,
+    mut text_query: Query<&mut Text, With<CellInspectorText>>,
+) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+    let clicked =
+        bindings.pressed(&keys, KeyAction::InspectCell) && mouse.just_pressed(MouseButton::Left);
+    if clicked {
+        *inspected = hovered_cell.position;
+    } else if !game_state.is_changed() {
+        return;
+    }
+    // A new level may not have the cell at all.
+    let position = inspected.filter(|&position| game_state.puzzle.is_valid(position));
+    *inspected = position;
+    let mut text = text_query.single_mut();
+    text.sections[0].value = match position {
+        Some(position) => describe_cell(&game_state.puzzle, &game_state.solution, position),
+        None => String::new(),
+    };
+}
+
+// Warns when the houses on the board can't be completed into any solution.
+fn update_dead_end_warning(
+    game_state: Res<GameState>,
+    mut text_query: Query<&mut Text, With<DeadEndText>>,
+    new_text_query: Query<(), Added<DeadEndText>>,
+) {
+    if !game_state.is_changed() && new_text_query.is_empty() {
+        return;
+    }
+    let puzzle = &game_state.puzzle;
+    let solution = &game_state.solution;
+    let dead_end = !puzzle.sandbox
+        && !solution.placements.is_empty()
+        && !game_state.is_solved()
+        && complete_solution(puzzle, solution).is_none();
+    let mut text = text_query.single_mut();
+    text.sections[0].value = if dead_end {
+        "No valid solution from here, remove a house to continue".to_string()
+    } else {
+        String::new()
+    };
+}
+
+fn update_violation_list(
+    game_state: Res<GameState>,
+    mut text_query: Query<&mut Text, With<ViolationListText>>,
+    new_text_query: Query<(), Added<ViolationListText>>,
+) {
+    if !game_state.is_changed() && new_text_query.is_empty() {
+        return;
+    }
+    let validation_result = validate_solution(&game_state.solution, &game_state.puzzle);
+    let mut text = text_query.single_mut();
+    text.sections[0].value = validation_result
+        .violation_lines(game_state.max_violations_shown)
+        .join("\n");
+}
+
+fn update_coverage(
+    game_state: Res<GameState>,
+    mut text_query: Query<&mut Text, With<CoverageText>>,
+    new_text_query: Query<(), Added<CoverageText>>,
+) {
+    if !game_state.is_changed() && new_text_query.is_empty() {
+        return;
+    }
+    for mut text in &mut text_query {
+        text.sections[0].value = format!(
+            "Coverage: {:.0}%",
+            coverage(&game_state.puzzle, &game_state.solution) * 100.0
+        );
+    }
+}
+
+fn update_action_log(
+    mut actions: EventReader<GameAction>,
+    mut action_log: ResMut<ActionLog>,
+    bindings: Res<KeyBindings>,
+    mut text_query: Query<&mut Text, With<ActionLogText>>,
+) {
+    if actions.is_empty() {
+        return;
+    }
+    for action in actions.iter() {
+        action_log.push(action.describe(&bindings));
     }
+    let mut text = text_query.single_mut();
+    text.sections[0].value = action_log
+        .entries
+        .iter()
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("\n");
 }
 
 // This system handles changing all buttons color based on mouse interaction
@@ -767,7 +1589,7 @@ fn button_system(
         (Changed<Interaction>, With<Button>),
     >,
     mut game_state: ResMut<GameState>,
-    mut app_state: ResMut<NextState<AppState>>,
+    mut transition: ResMut<ScreenTransition>,
     mut global_volume_settings: ResMut<GlobalVolumeSettings>,
     server: Res<AssetServer>,
 ) {
@@ -781,7 +1603,7 @@ fn button_system(
         if *interaction == Interaction::Pressed {
             match *action {
                 GameScreenButtonAction::Back => {
-                    app_state.set(AppState::MainMenuScreen);
+                    transition.start(AppState::MainMenuScreen);
                 }
                 GameScreenButtonAction::ToggleSound => {
                     if global_volume_settings.volume == 0.0 {
@@ -796,12 +1618,255 @@ fn button_system(
                     }
                 }
                 GameScreenButtonAction::Complete => {
-                    if game_state.current_level + 1 < all_levels().len() {
+                    // A second click during the fade would skip a level.
+                    if game_state.current_level + 1 < all_levels().len() && !transition.is_active()
+                    {
                         game_state.current_level += 1;
-                        app_state.set(AppState::SwitchLevel);
+                        transition.start(AppState::SwitchLevel);
                     }
                 }
             };
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn square_board_is_centered_vertically() {
+        assert_eq!(board_center(3, 3), Vec2::new(1.5 * CELL_SIZE, 0.0));
+    }
+
+    #[test]
+    fn fit_scale_never_zooms_in() {
+        let board = board_size(2, 2);
+        assert_eq!(fit_scale(board, Vec2::new(1280.0, 720.0)), 1.0);
+    }
+
+    #[test]
+    fn fit_scale_shrinks_board_into_play_area() {
+        let board = board_size(2, 2);
+        // The play area is half the board's width once the margins are taken off.
+        let window = Vec2::new(
+            board.x * 0.5 + 2.0 * BOARD_MARGIN,
+            HUD_HEIGHT + board.y + 2.0 * BOARD_MARGIN,
+        );
+        assert_eq!(fit_scale(board, window), 2.0);
+        // The HUD takes height away from the board, not width.
+        let window = Vec2::new(
+            board.x + 2.0 * BOARD_MARGIN,
+            HUD_HEIGHT + board.y * 0.5 + 2.0 * BOARD_MARGIN,
+        );
+        assert_eq!(fit_scale(board, window), 2.0);
+    }
+
+    #[test]
+    fn solved_message_names_bound_key() {
+        let mut bindings = KeyBindings::default();
+        assert_eq!(
+            GameAction::Solved.describe(&bindings),
+            "Solution valid! Press E to keep editing"
+        );
+        bindings
+            .keys
+            .insert(KeyAction::KeepEditing, vec![KeyCode::K]);
+        assert_eq!(
+            GameAction::Solved.describe(&bindings),
+            "Solution valid! Press K to keep editing"
+        );
+    }
+
+    #[test]
+    fn level_transition_completes_in_time() {
+        let mut transition = LevelTransition::default();
+        let half = Duration::from_secs_f32(LEVEL_TRANSITION_SECONDS * 0.5);
+        transition.tween.advance(half);
+        assert!(!transition.tween.finished());
+        transition.tween.advance(half);
+        assert!(transition.tween.finished());
+        assert_eq!(transition.tween.value(), 1.0);
+    }
+
+    #[test]
+    fn only_moved_houses_are_updated() {
+        let at = |row, col| Position { row, col };
+        let old = [at(0, 0), at(1, 2), at(2, 0)];
+        assert_eq!(
+            changed_house_indices(&old, &[at(0, 0), at(1, 3), at(2, 0)]),
+            vec![1]
+        );
+        assert_eq!(changed_house_indices(&old, &old[..2]), vec![2]);
+        assert_eq!(
+            changed_house_indices(&old, &[at(0, 0), at(1, 2), at(2, 0), at(3, 3)]),
+            vec![3]
+        );
+        assert_eq!(changed_house_indices(&[], &old), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn actions_describe_themselves() {
+        let bindings = KeyBindings::default();
+        let position = Position { row: 1, col: 2 };
+        let cases = [
+            (GameAction::PlaceHouse(position), "Placed house at (1, 2)"),
+            (GameAction::RemoveHouse(position), "Removed house at (1, 2)"),
+            (GameAction::Mark(position), "Marked (1, 2)"),
+            (GameAction::Unmark(position), "Unmarked (1, 2)"),
+            (GameAction::ClearHouses, "Cleared all houses"),
+            (GameAction::ResetBoard, "Reset the board"),
+        ];
+        for (action, text) in cases {
+            assert_eq!(action.describe(&bindings), text);
+        }
+    }
+
+    #[test]
+    fn action_log_drops_oldest_entry_when_full() {
+        let mut log = ActionLog::default();
+        for i in 0..=ACTION_LOG_SIZE {
+            log.push(i.to_string());
+        }
+        assert_eq!(log.entries.len(), ACTION_LOG_SIZE);
+        assert_eq!(log.entries.front().unwrap(), "1");
+        assert_eq!(log.entries.back().unwrap(), &ACTION_LOG_SIZE.to_string());
+    }
+
+    // The point whose unprojected coordinates are (col, row), the inverse of board_point_to_cell.
+    fn board_point(col: f32, row: f32) -> Vec2 {
+        Vec2::new(
+            (col + row) * CELL_SIZE * 0.5,
+            (col - row) * CELL_SIZE * 0.25,
+        )
+    }
+
+    #[test]
+    fn corner_cells_are_found() {
+        let (rows, cols) = (3, 4);
+        for (row, col) in [(0, 0), (0, cols - 1), (rows - 1, 0), (rows - 1, cols - 1)] {
+            let center = board_point(col as f32 + 0.5, row as f32 + 0.5);
+            assert_eq!(
+                board_point_to_cell(center, rows, cols),
+                Some(Position { row, col })
+            );
+        }
+    }
+
+    #[test]
+    fn points_off_the_board_have_no_cell() {
+        let (rows, cols) = (3, 4);
+        assert_eq!(
+            board_point_to_cell(board_point(-0.1, 0.5), rows, cols),
+            None
+        );
+        assert_eq!(
+            board_point_to_cell(board_point(0.5, -0.1), rows, cols),
+            None
+        );
+        assert_eq!(board_point_to_cell(board_point(4.0, 2.5), rows, cols), None);
+        assert_eq!(board_point_to_cell(board_point(3.5, 3.0), rows, cols), None);
+    }
+
+    #[test]
+    fn points_just_inside_the_last_row_and_column() {
+        let (rows, cols) = (3, 4);
+        assert_eq!(
+            board_point_to_cell(board_point(3.99, 2.99), rows, cols),
+            Some(Position { row: 2, col: 3 })
+        );
+        assert_eq!(
+            board_point_to_cell(board_point(0.01, 0.01), rows, cols),
+            Some(Position { row: 0, col: 0 })
+        );
+    }
+
+    #[test]
+    fn coordinate_label_shows_row_then_column() {
+        assert_eq!(coordinate_label(Position { row: 1, col: 2 }), "(1, 2)");
+    }
+
+    #[test]
+    fn clicks_place_remove_mark_or_refuse() {
+        let puzzle = Puzzle {
+            field: parse_field(vec![".T", ".."]),
+            row_count: vec![0, 1],
+            col_count: vec![1, 0],
+            sandbox: false,
+        };
+        let empty = Solution::default();
+        let grass = Position { row: 1, col: 0 };
+        let tree = Position { row: 0, col: 1 };
+        let full_row = Position { row: 0, col: 0 };
+        let outcome = |solution: &Solution, strict, position, button| {
+            click_outcome(&puzzle, solution, strict, position, button)
+        };
+
+        assert_eq!(
+            outcome(&empty, true, grass, MouseButton::Left),
+            ClickOutcome::Place
+        );
+        assert_eq!(
+            outcome(&empty, false, tree, MouseButton::Left),
+            ClickOutcome::Invalid
+        );
+        assert_eq!(
+            outcome(&empty, true, full_row, MouseButton::Left),
+            ClickOutcome::Invalid
+        );
+        assert_eq!(
+            outcome(&empty, false, full_row, MouseButton::Left),
+            ClickOutcome::Place
+        );
+        assert_eq!(
+            outcome(&empty, false, grass, MouseButton::Right),
+            ClickOutcome::ToggleMark
+        );
+        assert_eq!(
+            outcome(&empty, false, tree, MouseButton::Right),
+            ClickOutcome::Ignore
+        );
+
+        let placed = Solution {
+            placements: vec![Placement::new(grass)],
+        };
+        for button in [MouseButton::Left, MouseButton::Right] {
+            assert_eq!(outcome(&placed, false, grass, button), ClickOutcome::Remove);
+        }
+    }
+
+    #[test]
+    fn reveal_places_one_house_per_step() {
+        let solution = parse_solution(vec!["x.x", "...", "x.."]);
+        let mut reveal = SolutionReveal::default();
+        reveal.start(&solution);
+        let step = Duration::from_secs_f32(REVEAL_STEP_SECONDS);
+        let board = Solution::default();
+
+        assert_eq!(reveal.advance(step / 2, &board), vec![]);
+        assert_eq!(
+            reveal.advance(step / 2, &board),
+            vec![Position { row: 0, col: 0 }]
+        );
+        assert_eq!(
+            reveal.advance(step * 2, &board),
+            vec![Position { row: 0, col: 2 }, Position { row: 2, col: 0 }]
+        );
+        assert_eq!(reveal.advance(step, &board), vec![]);
+    }
+
+    #[test]
+    fn reveal_skips_houses_already_placed() {
+        let solution = parse_solution(vec!["x.x", "...", "x.."]);
+        let mut reveal = SolutionReveal::default();
+        reveal.start(&solution);
+        let step = Duration::from_secs_f32(REVEAL_STEP_SECONDS);
+        let board = parse_solution(vec!["x.x", "...", "..."]);
+
+        assert_eq!(
+            reveal.advance(step, &board),
+            vec![Position { row: 2, col: 0 }]
+        );
+        assert!(reveal.pending.is_empty());
+    }
+}
@@ -1,30 +1,321 @@
-use crate::level::all_levels;
-use crate::{AppState, GameState, GlobalVolumeSettings};
+use crate::game_screen::{GameAction, HoveredCell, SolutionReveal, FIRST_VIOLATIONS_SHOWN};
+use crate::level::{all_levels, auto_place_forced, Solution};
+use crate::transition::ScreenTransition;
+use crate::{AppState, GameState, GlobalVolumeSettings, SolverCache};
 use bevy::prelude::*;
+use std::collections::HashMap;
+use std::fmt::Debug;
 
 pub struct GameInputPlugin;
 
 impl Plugin for GameInputPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, keyboard_input);
+        app.init_resource::<KeyBindings>()
+            .add_systems(Startup, load_key_bindings)
+            .add_systems(Update, keyboard_input);
+    }
+}
+
+// Actions that can be triggered from the keyboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyAction {
+    NextLevel,
+    PreviousLevel,
+    SelectLevel,
+    ToggleStrictBudget,
+    AutoPlace,
+    TogglePlaceable,
+    RemoveHoveredHouse,
+    ToggleCoordinates,
+    RevealSolution,
+    KeepEditing,
+    ClearHouses,
+    ResetBoard,
+    InspectCell,
+    ToggleAllViolations,
+    SkipTransition,
+}
+
+impl KeyAction {
+    const ALL: [KeyAction; 15] = [
+        KeyAction::NextLevel,
+        KeyAction::PreviousLevel,
+        KeyAction::SelectLevel,
+        KeyAction::ToggleStrictBudget,
+        KeyAction::AutoPlace,
+        KeyAction::TogglePlaceable,
+        KeyAction::RemoveHoveredHouse,
+        KeyAction::ToggleCoordinates,
+        KeyAction::RevealSolution,
+        KeyAction::KeepEditing,
+        KeyAction::ClearHouses,
+        KeyAction::ResetBoard,
+        KeyAction::InspectCell,
+        KeyAction::ToggleAllViolations,
+        KeyAction::SkipTransition,
+    ];
+}
+
+// Keys a bindings file can name, spelled as their KeyCode variants.
+#[rustfmt::skip]
+const BINDABLE_KEYS: [KeyCode; 58] = [
+    KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G,
+    KeyCode::H, KeyCode::I, KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N,
+    KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R, KeyCode::S, KeyCode::T, KeyCode::U,
+    KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+    KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+    KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+    KeyCode::Left, KeyCode::Up, KeyCode::Right, KeyCode::Down,
+    KeyCode::Space, KeyCode::Return, KeyCode::Back, KeyCode::Delete, KeyCode::Tab,
+    KeyCode::Escape,
+    KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6,
+    KeyCode::F7, KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12,
+];
+
+// Overrides for the default bindings are read from here at startup, if the file exists.
+const BINDINGS_PATH: &str = "bindings.txt";
+
+// Maps each keyboard action to its keys, so layouts other than QWERTY can rebind them.
+#[derive(Resource)]
+pub struct KeyBindings {
+    pub keys: HashMap<KeyAction, Vec<KeyCode>>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            keys: HashMap::from([
+                (KeyAction::NextLevel, vec![KeyCode::Right]),
+                (KeyAction::PreviousLevel, vec![KeyCode::Left]),
+                (KeyAction::SelectLevel, vec![KeyCode::L]),
+                (KeyAction::ToggleStrictBudget, vec![KeyCode::B]),
+                (KeyAction::AutoPlace, vec![KeyCode::A]),
+                (KeyAction::TogglePlaceable, vec![KeyCode::P]),
+                (
+                    KeyAction::RemoveHoveredHouse,
+                    vec![KeyCode::Delete, KeyCode::Back],
+                ),
+                (KeyAction::ToggleCoordinates, vec![KeyCode::C]),
+                (KeyAction::RevealSolution, vec![KeyCode::S]),
+                (KeyAction::KeepEditing, vec![KeyCode::E]),
+                (KeyAction::ClearHouses, vec![KeyCode::R]),
+                // Far from the other keys, since it also wipes the marks.
+                (KeyAction::ResetBoard, vec![KeyCode::F9]),
+                (KeyAction::InspectCell, vec![KeyCode::I]),
+                (KeyAction::ToggleAllViolations, vec![KeyCode::V]),
+                (KeyAction::SkipTransition, vec![KeyCode::Space]),
+            ]),
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn just_pressed(&self, keys: &Input<KeyCode>, action: KeyAction) -> bool {
+        self.keys
+            .get(&action)
+            .is_some_and(|bound| keys.any_just_pressed(bound.iter().copied()))
+    }
+
+    pub fn pressed(&self, keys: &Input<KeyCode>, action: KeyAction) -> bool {
+        self.keys
+            .get(&action)
+            .is_some_and(|bound| keys.any_pressed(bound.iter().copied()))
+    }
+
+    // Rebinds actions from lines of the form `NextLevel = D`, using the KeyAction and
+    // KeyCode variant names. A line replaces all default keys of its action, and
+    // repeating the action binds several keys. Blank lines and lines starting with
+    // '#' are skipped. Nothing changes if any line is malformed.
+    pub fn apply_overrides(&mut self, text: &str) -> Result<(), String> {
+        let mut keys = self.keys.clone();
+        let mut rebound = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((action, key)) = line.split_once('=') else {
+                return Err(format!("Line {}: expected `Action = Key`", index + 1));
+            };
+            let (action, key) = (action.trim(), key.trim());
+            let Some(action) = find_by_name(&KeyAction::ALL, action) else {
+                return Err(format!("Line {}: unknown action {}", index + 1, action));
+            };
+            let Some(key) = find_by_name(&BINDABLE_KEYS, key) else {
+                return Err(format!("Line {}: unknown key {}", index + 1, key));
+            };
+            let bound = keys.entry(action).or_default();
+            if !rebound.contains(&action) {
+                bound.clear();
+                rebound.push(action);
+            }
+            bound.push(key);
+        }
+        self.keys = keys;
+        Ok(())
+    }
+}
+
+fn find_by_name<T: Copy + Debug>(values: &[T], name: &str) -> Option<T> {
+    values
+        .iter()
+        .copied()
+        .find(|value| format!("{:?}", value) == name)
+}
+
+fn load_key_bindings(mut bindings: ResMut<KeyBindings>) {
+    let Ok(text) = std::fs::read_to_string(BINDINGS_PATH) else {
+        return;
+    };
+    if let Err(error) = bindings.apply_overrides(&text) {
+        warn!("Ignoring {}: {}", BINDINGS_PATH, error);
     }
 }
 
 fn keyboard_input(
     keys: Res<Input<KeyCode>>,
+    bindings: Res<KeyBindings>,
     mut game_state: ResMut<GameState>,
-    mut app_state: ResMut<NextState<AppState>>,
+    current_app_state: Res<State<AppState>>,
+    mut transition: ResMut<ScreenTransition>,
     mut global_volume_settings: ResMut<GlobalVolumeSettings>,
+    mut actions: EventWriter<GameAction>,
+    hovered_cell: Res<HoveredCell>,
+    mut solver_cache: ResMut<SolverCache>,
+    mut reveal: ResMut<SolutionReveal>,
 ) {
-    if keys.just_pressed(KeyCode::Right) && game_state.current_level + 1 < all_levels().len() {
+    // Level changes wait for the current fade, so repeated presses can't skip levels.
+    if bindings.just_pressed(&keys, KeyAction::NextLevel)
+        && game_state.current_level + 1 < all_levels().len()
+        && !transition.is_active()
+    {
         game_state.current_level += 1;
-        app_state.set(AppState::SwitchLevel);
+        transition.start(AppState::SwitchLevel);
     }
-    if keys.just_pressed(KeyCode::Left) && game_state.current_level > 0 {
+    if bindings.just_pressed(&keys, KeyAction::PreviousLevel)
+        && game_state.current_level > 0
+        && !transition.is_active()
+    {
         game_state.current_level -= 1;
-        app_state.set(AppState::SwitchLevel);
+        transition.start(AppState::SwitchLevel);
+    }
+    if bindings.just_pressed(&keys, KeyAction::SelectLevel) {
+        transition.start(AppState::SelectLevelScreen);
+    }
+    if bindings.just_pressed(&keys, KeyAction::ToggleStrictBudget) {
+        game_state.strict_budget = !game_state.strict_budget;
+    }
+    if bindings.just_pressed(&keys, KeyAction::TogglePlaceable) {
+        game_state.show_placeable = !game_state.show_placeable;
+    }
+    if bindings.just_pressed(&keys, KeyAction::ToggleCoordinates) {
+        game_state.show_coordinates = !game_state.show_coordinates;
+    }
+    if bindings.just_pressed(&keys, KeyAction::ToggleAllViolations) {
+        game_state.max_violations_shown = if game_state.max_violations_shown == usize::MAX {
+            FIRST_VIOLATIONS_SHOWN
+        } else {
+            usize::MAX
+        };
+    }
+    if bindings.just_pressed(&keys, KeyAction::ClearHouses)
+        && *current_app_state.get() == AppState::InGame
+    {
+        reveal.cancel();
+        game_state.clear_houses();
+        actions.send(GameAction::ClearHouses);
+    }
+    if bindings.just_pressed(&keys, KeyAction::ResetBoard)
+        && *current_app_state.get() == AppState::InGame
+    {
+        reveal.cancel();
+        game_state.reset_board();
+        actions.send(GameAction::ResetBoard);
+    }
+    if bindings.just_pressed(&keys, KeyAction::KeepEditing) {
+        game_state.locked = false;
+    }
+    if bindings.just_pressed(&keys, KeyAction::AutoPlace)
+        && *current_app_state.get() == AppState::InGame
+        && !game_state.locked
+    {
+        reveal.cancel();
+        for placement in auto_place_forced(&game_state.puzzle, &game_state.solution) {
+            let position = placement.position;
+            game_state.hints[position.row][position.col] = false;
+            game_state.solution.placements.push(placement);
+            actions.send(GameAction::PlaceHouse(position));
+        }
+    }
+    // Clears the board and places the solver's houses one by one.
+    if bindings.just_pressed(&keys, KeyAction::RevealSolution)
+        && *current_app_state.get() == AppState::InGame
+        && !game_state.locked
+    {
+        if let Some(solution) = solver_cache.solve(&game_state.puzzle) {
+            reveal.start(solution);
+            game_state.solution = Solution::default();
+            game_state.revealed = true;
+        }
     }
-    if keys.just_pressed(KeyCode::L) {
-        app_state.set(AppState::SelectLevelScreen);
+    if bindings.just_pressed(&keys, KeyAction::RemoveHoveredHouse)
+        && *current_app_state.get() == AppState::InGame
+        && !game_state.locked
+    {
+        if let Some(position) = hovered_cell.position {
+            if game_state.remove_house(position) {
+                actions.send(GameAction::RemoveHouse(position));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_binding_overrides_default() {
+        let mut bindings = KeyBindings::default();
+        bindings
+            .apply_overrides("# Arrows are taken on this keyboard\nNextLevel = D\n")
+            .unwrap();
+        assert_eq!(bindings.keys[&KeyAction::NextLevel], vec![KeyCode::D]);
+        assert_eq!(
+            bindings.keys[&KeyAction::PreviousLevel],
+            vec![KeyCode::Left]
+        );
+    }
+
+    #[test]
+    fn malformed_bindings_change_nothing() {
+        let mut bindings = KeyBindings::default();
+        assert!(bindings
+            .apply_overrides("NextLevel = D\nJump = Space\n")
+            .is_err());
+        assert_eq!(bindings.keys[&KeyAction::NextLevel], vec![KeyCode::Right]);
+    }
+
+    #[test]
+    fn repeated_action_binds_several_keys() {
+        let mut bindings = KeyBindings::default();
+        bindings
+            .apply_overrides("NextLevel = D\nNextLevel = Tab\n")
+            .unwrap();
+        assert_eq!(
+            bindings.keys[&KeyAction::NextLevel],
+            vec![KeyCode::D, KeyCode::Tab]
+        );
+    }
+
+    #[test]
+    fn backspace_removes_a_house_without_resetting() {
+        let bindings = KeyBindings::default();
+        for key in [KeyCode::Back, KeyCode::Delete] {
+            let mut keys = Input::default();
+            keys.press(key);
+            assert!(bindings.just_pressed(&keys, KeyAction::RemoveHoveredHouse));
+            assert!(!bindings.just_pressed(&keys, KeyAction::ResetBoard));
+        }
     }
 }
@@ -1,6 +1,9 @@
 use core::fmt;
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+// Levels and solutions are written as grids of characters, one string per row:
+// '.' grass, 'T' tree, 'L' lake, 'M' mountain (see CellType::to_char), and
+// HOUSE_CHAR for a house, which always stands on grass.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum CellType {
     Grass,
     Tree,
@@ -29,11 +32,13 @@ impl CellType {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Puzzle {
     pub row_count: Vec<usize>,
     pub col_count: Vec<usize>,
     pub field: Vec<Vec<CellType>>,
+    // Sandbox puzzles have no constraints and are never complete.
+    pub sandbox: bool,
 }
 
 impl Puzzle {
@@ -49,8 +54,73 @@ impl Puzzle {
         (self.rows(), self.cols())
     }
 
-    pub fn is_valid(&self, row: i32, col: i32) -> bool {
-        row >= 0 && row < self.rows() as i32 && col >= 0 && col < self.cols() as i32
+    pub fn is_valid(&self, position: Position) -> bool {
+        position.in_bounds(self.rows(), self.cols())
+    }
+
+    // A stable identifier derived from the field and the counts, so progress
+    // survives reordering the levels. This is FNV-1a, which unlike the std
+    // hasher is the same on every build and platform.
+    pub fn id(&self) -> u64 {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        let mut feed = |byte: u8| {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        };
+        for row in &self.field {
+            for cell in row {
+                feed(cell.to_char() as u8);
+            }
+            feed(b'\n');
+        }
+        // The field fixes how many counts there are, so they need no separator.
+        for count in self.row_count.iter().chain(&self.col_count) {
+            for byte in (*count as u64).to_le_bytes() {
+                feed(byte);
+            }
+        }
+        hash
+    }
+
+    // The cell shifted by the given amounts, if it is still inside the field.
+    pub fn neighbor(&self, position: Position, drow: i32, dcol: i32) -> Option<Position> {
+        position
+            .offset(drow, dcol)
+            .filter(|&neighbor| self.is_valid(neighbor))
+    }
+
+    // Mirrors the puzzle and the solution's houses left to right.
+    pub fn flip_horizontal(&mut self, solution: &mut Solution) {
+        let cols = self.cols();
+        for row in &mut self.field {
+            row.reverse();
+        }
+        self.col_count.reverse();
+        for placement in &mut solution.placements {
+            placement.position.col = cols - 1 - placement.position.col;
+        }
+    }
+
+    // Turns the puzzle and the solution's houses a quarter turn clockwise.
+    pub fn rotate_90(&mut self, solution: &mut Solution) {
+        let (rows, cols) = self.dims();
+        self.field = (0..cols)
+            .map(|row| {
+                (0..rows)
+                    .map(|col| self.field[rows - 1 - col][row])
+                    .collect()
+            })
+            .collect();
+        let mut col_count = std::mem::replace(&mut self.row_count, self.col_count.clone());
+        col_count.reverse();
+        self.col_count = col_count;
+        for placement in &mut solution.placements {
+            let Position { row, col } = placement.position;
+            placement.position = Position {
+                row: col,
+                col: rows - 1 - row,
+            };
+        }
     }
 }
 
@@ -68,16 +138,32 @@ impl fmt::Display for Puzzle {
     }
 }
 
+// The game screen spawns sprites per cell and a house sprite per cell up front,
+// so fields are capped at this many cells.
+pub const MAX_CELLS: usize = 100;
+
+// An all-grass field, or an error when it would have more than MAX_CELLS cells.
+pub fn checked_field_from_size(rows: usize, cols: usize) -> Result<Vec<Vec<CellType>>, String> {
+    if rows.checked_mul(cols).is_none_or(|cells| cells > MAX_CELLS) {
+        return Err(format!(
+            "Field of {}x{} exceeds {} cells",
+            rows, cols, MAX_CELLS
+        ));
+    }
+    Ok(vec![vec![CellType::Grass; cols]; rows])
+}
+
+// Like checked_field_from_size, for built-in sizes that are known to fit.
 pub fn field_from_size(rows: usize, cols: usize) -> Vec<Vec<CellType>> {
-    vec![vec![CellType::Grass; cols]; rows]
+    checked_field_from_size(rows, cols).unwrap_or_else(|error| panic!("{}", error))
 }
 
 pub fn parse_field(s: Vec<&str>) -> Vec<Vec<CellType>> {
     let mut field = field_from_size(s.len(), s[0].len());
     for (row, line) in s.iter().enumerate() {
         for (col, c) in line.as_bytes().iter().enumerate() {
-            // Skip cells with house objects.
-            if [b'x'].contains(c) {
+            // Houses stand on grass, which the field already defaults to.
+            if *c == HOUSE_CHAR as u8 {
                 continue;
             }
             field[row][col] = CellType::from_char(*c);
@@ -86,28 +172,111 @@ pub fn parse_field(s: Vec<&str>) -> Vec<Vec<CellType>> {
     field
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub const HOUSE_CHAR: char = 'x';
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct Position {
     pub row: usize,
     pub col: usize,
 }
 
-#[derive(Debug)]
+impl Position {
+    // The position shifted by the given amounts, or None if it would go below zero.
+    pub fn offset(self, drow: i32, dcol: i32) -> Option<Position> {
+        Some(Position {
+            row: self.row.checked_add_signed(drow as isize)?,
+            col: self.col.checked_add_signed(dcol as isize)?,
+        })
+    }
+
+    pub fn manhattan_distance(self, other: Position) -> usize {
+        self.row.abs_diff(other.row) + self.col.abs_diff(other.col)
+    }
+
+    pub fn in_bounds(self, rows: usize, cols: usize) -> bool {
+        self.row < rows && self.col < cols
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Placement {
     pub position: Position,
 }
 
-#[derive(Debug, Default)]
+impl Placement {
+    pub fn new(position: Position) -> Placement {
+        Placement { position }
+    }
+
+    // Builds a placement only if a house can stand on the cell at all.
+    pub fn try_place(puzzle: &Puzzle, position: Position) -> Result<Placement, ViolationType> {
+        if !puzzle.is_valid(position) {
+            return Err(ViolationType::OutOfBounds);
+        }
+        if puzzle.field[position.row][position.col] != CellType::Grass {
+            return Err(ViolationType::NotOnGrass);
+        }
+        Ok(Placement::new(position))
+    }
+}
+
+#[derive(Debug, Default, Clone)]
 pub struct Solution {
     pub placements: Vec<Placement>,
 }
 
+// Reads the houses from a grid in the level format, ignoring the terrain. Levels
+// don't ship solutions, so only tests write boards this way.
+#[cfg(test)]
+pub fn parse_solution(s: Vec<&str>) -> Solution {
+    let mut solution = Solution::default();
+    for (row, line) in s.iter().enumerate() {
+        for (col, c) in line.chars().enumerate() {
+            if c == HOUSE_CHAR {
+                solution
+                    .placements
+                    .push(Placement::new(Position { row, col }));
+            }
+        }
+    }
+    solution
+}
+
+// Draws the field with the solution's houses on top, one line per row. This is
+// the inverse of parse_field and parse_solution on the same grid.
+pub fn render_ascii(puzzle: &Puzzle, solution: &Solution) -> String {
+    let mut grid: Vec<Vec<char>> = puzzle
+        .field
+        .iter()
+        .map(|row| row.iter().map(|cell| cell.to_char()).collect())
+        .collect();
+    for placement in &solution.placements {
+        grid[placement.position.row][placement.position.col] = HOUSE_CHAR;
+    }
+    grid.iter()
+        .map(|row| row.iter().collect::<String>() + "\n")
+        .collect()
+}
+
 const DROW: [i32; 4] = [1, 0, -1, 0];
 const DCOL: [i32; 4] = [0, 1, 0, -1];
 
 #[derive(Debug)]
 pub enum ViolationType {
     AdjacentHouse,
+    OutOfBounds,
+    NotOnGrass,
+}
+
+impl ViolationType {
+    // Player-facing explanation, shared by the status text and tooltips.
+    pub fn description(&self) -> &'static str {
+        match self {
+            ViolationType::AdjacentHouse => "This house has another house right next to it",
+            ViolationType::OutOfBounds => "This spot is outside the island",
+            ViolationType::NotOnGrass => "Houses can only be built on grass",
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -145,22 +314,70 @@ pub struct ValidationResult {
     pub complete: bool,
 }
 
+impl ValidationResult {
+    // Number of broken rules: mismatched lines, misplaced houses and unsatisfied lakes and mountains.
+    pub fn error_count(&self) -> usize {
+        let lines = self
+            .row_status
+            .iter()
+            .chain(&self.col_status)
+            .filter(|status| !matches!(status, LineStatus::Match))
+            .count();
+        let constraints = self
+            .constraint_violations
+            .iter()
+            .filter(|v| !matches!(v.violation, ConstraintViolationType::Match))
+            .count();
+        lines + self.placement_violations.len() + constraints
+    }
+
+    // One line per placement violation, listing at most `max_shown` of them and
+    // summing up the rest as "...and K more".
+    pub fn violation_lines(&self, max_shown: usize) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .placement_violations
+            .iter()
+            .take(max_shown)
+            .map(|violation| {
+                format!(
+                    "House #{}: {}",
+                    violation.house_index,
+                    violation.violation.description()
+                )
+            })
+            .collect();
+        if self.placement_violations.len() > max_shown {
+            lines.push(format!(
+                "...and {} more",
+                self.placement_violations.len() - max_shown
+            ));
+        }
+        lines
+    }
+}
+
 impl fmt::Display for ValidationResult {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(formatter, "Rows: {:?}", self.row_status)?;
         writeln!(formatter, "Columns: {:?}", self.col_status)?;
-        for violation in &self.placement_violations {
-            writeln!(
-                formatter,
-                "{}: {:?}",
-                violation.house_index, violation.violation
-            )?
+        for line in self.violation_lines(self.placement_violations.len()) {
+            writeln!(formatter, "{}", line)?
         }
         Ok(())
     }
 }
 
 pub fn validate_solution(solution: &Solution, puzzle: &Puzzle) -> ValidationResult {
+    if puzzle.sandbox {
+        return ValidationResult {
+            row_status: vec![LineStatus::Match; puzzle.rows()],
+            col_status: vec![LineStatus::Match; puzzle.cols()],
+            placement_violations: Vec::new(),
+            constraint_violations: Vec::new(),
+            complete: false,
+        };
+    }
+
     let mut placement_violations = Vec::new();
 
     let mut has_house = vec![vec![false; puzzle.cols()]; puzzle.rows()];
@@ -236,24 +453,15 @@ pub fn validate_solution(solution: &Solution, puzzle: &Puzzle) -> ValidationResu
         }
     }
 
-    let complete = row_status
-        .iter()
-        .all(|status| matches!(status, LineStatus::Match))
-        && col_status
-            .iter()
-            .all(|status| matches!(status, LineStatus::Match))
-        && placement_violations.is_empty()
-        && constraint_violations
-            .iter()
-            .all(|v| matches!(v.violation, ConstraintViolationType::Match));
-
-    ValidationResult {
+    let mut result = ValidationResult {
         row_status,
         col_status,
         placement_violations,
         constraint_violations,
-        complete,
-    }
+        complete: false,
+    };
+    result.complete = result.error_count() == 0;
+    result
 }
 
 pub fn count_diagnoal_houses(
@@ -265,15 +473,12 @@ pub fn count_diagnoal_houses(
     let mut count = 0;
     for drow in [-1, 1] {
         for dcol in [-1, 1] {
-            for d in 1..puzzle.rows().max(puzzle.cols()) {
-                let nrow = row as i32 + drow * d as i32;
-                let ncol = col as i32 + dcol * d as i32;
-                if !puzzle.is_valid(nrow, ncol) {
+            for d in 1..puzzle.rows().max(puzzle.cols()) as i32 {
+                let Some(neighbor) = puzzle.neighbor(Position { row, col }, drow * d, dcol * d)
+                else {
                     break;
-                }
-                let nrow = nrow as usize;
-                let ncol = ncol as usize;
-                if has_house[nrow][ncol] {
+                };
+                if has_house[neighbor.row][neighbor.col] {
                     count += 1;
                 }
             }
@@ -291,14 +496,10 @@ pub fn count_houses_in_3x3(
     let mut count = 0;
     for drow in -1..=1 {
         for dcol in -1..=1 {
-            let nrow = row as i32 + drow;
-            let ncol = col as i32 + dcol;
-            if !puzzle.is_valid(nrow, ncol) {
+            let Some(neighbor) = puzzle.neighbor(Position { row, col }, drow, dcol) else {
                 continue;
-            }
-            let nrow = nrow as usize;
-            let ncol = ncol as usize;
-            if has_house[nrow][ncol] {
+            };
+            if has_house[neighbor.row][neighbor.col] {
                 count += 1;
             }
         }
@@ -314,15 +515,10 @@ pub fn count_adjacent_houses(
 ) -> usize {
     let mut count = 0;
     for d in 0..4 {
-        let nrow = row as i32 + DROW[d];
-        let ncol = col as i32 + DCOL[d];
-        if !puzzle.is_valid(nrow, ncol) {
+        let Some(neighbor) = puzzle.neighbor(Position { row, col }, DROW[d], DCOL[d]) else {
             continue;
-        }
-
-        let nrow = nrow as usize;
-        let ncol = ncol as usize;
-        if has_house[nrow][ncol] {
+        };
+        if has_house[neighbor.row][neighbor.col] {
             count += 1;
             break;
         }
@@ -330,6 +526,445 @@ pub fn count_adjacent_houses(
     count
 }
 
+// Player-facing rules that apply to the puzzle, for the summary shown at level start.
+pub fn active_rules(puzzle: &Puzzle) -> Vec<&'static str> {
+    if puzzle.sandbox {
+        return vec!["Build anywhere on grass, there are no rules"];
+    }
+    let mut rules = vec![
+        "Houses can only be built on grass",
+        "Houses can't be next to each other",
+        "Each row and column needs as many houses as its number",
+    ];
+    let has_cell =
+        |cell_type: CellType| puzzle.field.iter().flatten().any(|&cell| cell == cell_type);
+    if has_cell(CellType::Lake) {
+        rules.push("A lake needs exactly 3 houses around it");
+    }
+    if has_cell(CellType::Mountain) {
+        rules.push("A mountain needs exactly 2 houses on its diagonals");
+    }
+    rules
+}
+
+// Houses still to place in each row and column; negative when a line has too many.
+#[derive(Debug)]
+pub struct RemainingHouses {
+    pub rows: Vec<i64>,
+    pub cols: Vec<i64>,
+}
+
+pub fn remaining_houses(puzzle: &Puzzle, solution: &Solution) -> RemainingHouses {
+    let mut remaining = RemainingHouses {
+        rows: puzzle.row_count.iter().map(|&count| count as i64).collect(),
+        cols: puzzle.col_count.iter().map(|&count| count as i64).collect(),
+    };
+    for placement in &solution.placements {
+        remaining.rows[placement.position.row] -= 1;
+        remaining.cols[placement.position.col] -= 1;
+    }
+    remaining
+}
+
+// Checks that adding a house at the position keeps its row and column within the required counts.
+pub fn within_budget(puzzle: &Puzzle, solution: &Solution, position: Position) -> bool {
+    if puzzle.sandbox {
+        return true;
+    }
+    let remaining = remaining_houses(puzzle, solution);
+    remaining.rows[position.row] > 0 && remaining.cols[position.col] > 0
+}
+
+// Checks only the rules that a single new house can break on its own: it must be
+// on a free grass cell inside the field with no house next to it. This is cheap
+// enough for the solver's inner loop; validate_solution remains the full check.
+pub fn is_placement_locally_valid(
+    puzzle: &Puzzle,
+    has_house: &Vec<Vec<bool>>,
+    position: Position,
+) -> bool {
+    let (row, col) = (position.row, position.col);
+    puzzle.is_valid(position)
+        && puzzle.field[row][col] == CellType::Grass
+        && !has_house[row][col]
+        && count_adjacent_houses(row, col, has_house, puzzle) == 0
+}
+
+fn house_grid(puzzle: &Puzzle, solution: &Solution) -> Vec<Vec<bool>> {
+    let mut has_house = vec![vec![false; puzzle.cols()]; puzzle.rows()];
+    for placement in &solution.placements {
+        has_house[placement.position.row][placement.position.col] = true;
+    }
+    has_house
+}
+
+// Debug summary of a cell: its terrain, its house and that house's violations,
+// and the terrain of its in-bounds neighbours.
+pub fn describe_cell(puzzle: &Puzzle, solution: &Solution, position: Position) -> String {
+    let Position { row, col } = position;
+    let mut lines = vec![format!(
+        "Cell ({}, {}): {:?}",
+        row, col, puzzle.field[row][col]
+    )];
+
+    match solution
+        .placements
+        .iter()
+        .position(|placement| placement.position == position)
+    {
+        Some(index) => {
+            lines.push(format!("House #{}", index));
+            let validation = validate_solution(solution, puzzle);
+            for violation in &validation.placement_violations {
+                if violation.house_index == index {
+                    lines.push(violation.violation.description().to_string());
+                }
+            }
+        }
+        None => lines.push("No house".to_string()),
+    }
+
+    let has_house = house_grid(puzzle, solution);
+    for d in 0..4 {
+        if let Some(neighbor) = puzzle.neighbor(position, DROW[d], DCOL[d]) {
+            let house = if has_house[neighbor.row][neighbor.col] {
+                ", house"
+            } else {
+                ""
+            };
+            lines.push(format!(
+                "Neighbor ({}, {}): {:?}{}",
+                neighbor.row, neighbor.col, puzzle.field[neighbor.row][neighbor.col], house
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+// Marks the cells where one more house would break no placement rule.
+pub fn placeable_cells(puzzle: &Puzzle, solution: &Solution) -> Vec<Vec<bool>> {
+    let has_house = house_grid(puzzle, solution);
+    (0..puzzle.rows())
+        .map(|row| {
+            (0..puzzle.cols())
+                .map(|col| is_placement_locally_valid(puzzle, &has_house, Position { row, col }))
+                .collect()
+        })
+        .collect()
+}
+
+// Fraction of grass cells that hold a house or are orthogonally next to one,
+// a rough measure of how full a layout is. A field without grass counts as
+// fully covered and returns 1.0.
+pub fn coverage(puzzle: &Puzzle, solution: &Solution) -> f32 {
+    let has_house = house_grid(puzzle, solution);
+    let mut grass = 0;
+    let mut covered = 0;
+    for row in 0..puzzle.rows() {
+        for col in 0..puzzle.cols() {
+            if puzzle.field[row][col] != CellType::Grass {
+                continue;
+            }
+            grass += 1;
+            if has_house[row][col] || count_adjacent_houses(row, col, &has_house, puzzle) > 0 {
+                covered += 1;
+            }
+        }
+    }
+    if grass == 0 {
+        1.0
+    } else {
+        covered as f32 / grass as f32
+    }
+}
+
+// Finds houses forced by the row and column counts: when a line has exactly as
+// many open cells as houses still missing, every one of them must hold a house.
+// Lines with no open cells, or a forced pair of neighbours, are left alone.
+pub fn auto_place_forced(puzzle: &Puzzle, solution: &Solution) -> Vec<Placement> {
+    if puzzle.sandbox {
+        return Vec::new();
+    }
+    let (rows, cols) = puzzle.dims();
+    let has_house = house_grid(puzzle, solution);
+    let remaining = remaining_houses(puzzle, solution);
+    let open = |position: Position| {
+        remaining.rows[position.row] > 0
+            && remaining.cols[position.col] > 0
+            && is_placement_locally_valid(puzzle, &has_house, position)
+    };
+
+    let row_lines = (0..rows).map(|row| {
+        let cells: Vec<Position> = (0..cols).map(|col| Position { row, col }).collect();
+        (remaining.rows[row], cells)
+    });
+    let col_lines = (0..cols).map(|col| {
+        let cells: Vec<Position> = (0..rows).map(|row| Position { row, col }).collect();
+        (remaining.cols[col], cells)
+    });
+
+    let mut forced: Vec<Position> = Vec::new();
+    for (missing, cells) in row_lines.chain(col_lines) {
+        let candidates: Vec<Position> = cells.into_iter().filter(|&p| open(p)).collect();
+        let separated = candidates
+            .windows(2)
+            .all(|pair| pair[0].manhattan_distance(pair[1]) > 1);
+        if missing > 0 && candidates.len() as i64 == missing && separated {
+            for position in candidates {
+                if !forced.contains(&position) {
+                    forced.push(position);
+                }
+            }
+        }
+    }
+    forced.into_iter().map(Placement::new).collect()
+}
+
+// Finds up to `cap` solutions by trying cells in row-major order and pruning on
+// the row/column counts and house adjacency. Lake and mountain constraints are
+// checked once the field is filled. The search order is fixed, so the same
+// puzzle always yields the same solutions in the same order.
+pub fn solve_up_to(puzzle: &Puzzle, cap: usize) -> Vec<Solution> {
+    let fixed = vec![vec![false; puzzle.cols()]; puzzle.rows()];
+    search_with_fixed(puzzle, &fixed, cap)
+}
+
+pub fn solve(puzzle: &Puzzle) -> Option<Solution> {
+    solve_up_to(puzzle, 1).pop()
+}
+
+// Finds a solution that keeps every house already in `solution`, or None when
+// those houses can't be part of any solution.
+pub fn complete_solution(puzzle: &Puzzle, solution: &Solution) -> Option<Solution> {
+    if !solution
+        .placements
+        .iter()
+        .all(|placement| puzzle.is_valid(placement.position))
+    {
+        return None;
+    }
+    let fixed = house_grid(puzzle, solution);
+    search_with_fixed(puzzle, &fixed, 1).pop()
+}
+
+fn search_with_fixed(puzzle: &Puzzle, fixed: &Vec<Vec<bool>>, cap: usize) -> Vec<Solution> {
+    let mut has_house = vec![vec![false; puzzle.cols()]; puzzle.rows()];
+    let mut col_houses = vec![0; puzzle.cols()];
+    let mut solutions = Vec::new();
+    search(
+        puzzle,
+        fixed,
+        0,
+        0,
+        &mut has_house,
+        &mut col_houses,
+        cap,
+        &mut solutions,
+    );
+    solutions
+}
+
+// Cells marked in `fixed` must hold a house; every other cell may go either way.
+#[allow(clippy::too_many_arguments)]
+fn search(
+    puzzle: &Puzzle,
+    fixed: &Vec<Vec<bool>>,
+    cell: usize,
+    row_houses: usize,
+    has_house: &mut Vec<Vec<bool>>,
+    col_houses: &mut [usize],
+    cap: usize,
+    solutions: &mut Vec<Solution>,
+) {
+    if solutions.len() >= cap {
+        return;
+    }
+    let (rows, cols) = puzzle.dims();
+    if cell == rows * cols {
+        if *col_houses != puzzle.col_count[..] {
+            return;
+        }
+        let mut solution = Solution::default();
+        for (row, cells) in has_house.iter().enumerate() {
+            for (col, &house) in cells.iter().enumerate() {
+                if house {
+                    solution
+                        .placements
+                        .push(Placement::new(Position { row, col }));
+                }
+            }
+        }
+        if validate_solution(&solution, puzzle).complete {
+            solutions.push(solution);
+        }
+        return;
+    }
+
+    let (row, col) = (cell / cols, cell % cols);
+    // Not enough cells left in this row to reach its count.
+    if row_houses + (cols - col) < puzzle.row_count[row] {
+        return;
+    }
+    // The next cell starts a new row, so the current one has to be complete.
+    let row_done = |houses: usize| col + 1 < cols || houses == puzzle.row_count[row];
+    let next_row_houses = |houses: usize| if col + 1 < cols { houses } else { 0 };
+
+    if row_houses < puzzle.row_count[row]
+        && col_houses[col] < puzzle.col_count[col]
+        && is_placement_locally_valid(puzzle, has_house, Position { row, col })
+        && row_done(row_houses + 1)
+    {
+        has_house[row][col] = true;
+        col_houses[col] += 1;
+        search(
+            puzzle,
+            fixed,
+            cell + 1,
+            next_row_houses(row_houses + 1),
+            has_house,
+            col_houses,
+            cap,
+            solutions,
+        );
+        col_houses[col] -= 1;
+        has_house[row][col] = false;
+    }
+
+    if !fixed[row][col] && row_done(row_houses) {
+        search(
+            puzzle,
+            fixed,
+            cell + 1,
+            next_row_houses(row_houses),
+            has_house,
+            col_houses,
+            cap,
+            solutions,
+        );
+    }
+}
+
+// Levels with more solutions than this are only reported as ambiguous.
+pub const AUDIT_SOLUTION_CAP: usize = 10;
+
+#[derive(Debug)]
+pub struct LevelAudit {
+    pub name: String,
+    // Number of solutions found, up to AUDIT_SOLUTION_CAP.
+    pub solution_count: usize,
+    // Authoring mistakes found by sanity_issues; the level isn't solved when there are any.
+    pub issues: Vec<String>,
+}
+
+impl LevelAudit {
+    pub fn solvable(&self) -> bool {
+        self.solution_count > 0
+    }
+
+    pub fn unique(&self) -> bool {
+        self.solution_count == 1
+    }
+}
+
+pub fn audit_levels(levels: &[GameLevel]) -> Vec<LevelAudit> {
+    levels
+        .iter()
+        .map(|level| {
+            let issues = sanity_issues(&level.puzzle);
+            let solutions = if issues.is_empty() {
+                solve_up_to(&level.puzzle, AUDIT_SOLUTION_CAP)
+            } else {
+                Vec::new()
+            };
+            LevelAudit {
+                name: level.name.clone(),
+                solution_count: solutions.len(),
+                issues,
+            }
+        })
+        .collect()
+}
+
+// Cheap checks for counts that can't be satisfied, such as more houses than grass.
+pub fn sanity_issues(puzzle: &Puzzle) -> Vec<String> {
+    let (rows, cols) = puzzle.dims();
+    if puzzle.row_count.len() != rows || puzzle.col_count.len() != cols {
+        return vec![format!(
+            "{} row and {} column counts for a {}x{} field",
+            puzzle.row_count.len(),
+            puzzle.col_count.len(),
+            rows,
+            cols
+        )];
+    }
+
+    let mut issues = Vec::new();
+    let row_total: usize = puzzle.row_count.iter().sum();
+    let col_total: usize = puzzle.col_count.iter().sum();
+    if row_total != col_total {
+        issues.push(format!(
+            "Row counts add up to {} but column counts to {}",
+            row_total, col_total
+        ));
+    }
+    let is_grass = |row: usize, col: usize| puzzle.field[row][col] == CellType::Grass;
+    let grass_total = (0..rows)
+        .flat_map(|row| (0..cols).map(move |col| (row, col)))
+        .filter(|&(row, col)| is_grass(row, col))
+        .count();
+    if row_total > grass_total {
+        issues.push(format!(
+            "{} houses required but only {} grass cells",
+            row_total, grass_total
+        ));
+    }
+    for row in 0..rows {
+        let grass = (0..cols).filter(|&col| is_grass(row, col)).count();
+        if puzzle.row_count[row] > grass {
+            issues.push(format!("Row {} needs more houses than it has grass", row));
+        }
+    }
+    for col in 0..cols {
+        let grass = (0..rows).filter(|&row| is_grass(row, col)).count();
+        if puzzle.col_count[col] > grass {
+            issues.push(format!(
+                "Column {} needs more houses than it has grass",
+                col
+            ));
+        }
+    }
+    issues
+}
+
+// Every orientation of the puzzle reachable by rotating and flipping, itself included.
+fn orientations(puzzle: &Puzzle) -> Vec<Puzzle> {
+    let mut puzzle = puzzle.clone();
+    let mut solution = Solution::default();
+    let mut result = Vec::new();
+    for _ in 0..4 {
+        puzzle.rotate_90(&mut solution);
+        result.push(puzzle.clone());
+        puzzle.flip_horizontal(&mut solution);
+        result.push(puzzle.clone());
+        puzzle.flip_horizontal(&mut solution);
+    }
+    result
+}
+
+// Index pairs of levels that are the same puzzle up to rotation and flipping.
+pub fn find_duplicates(levels: &[GameLevel]) -> Vec<(usize, usize)> {
+    let mut duplicates = Vec::new();
+    for (i, level) in levels.iter().enumerate() {
+        let variants = orientations(&level.puzzle);
+        for (j, other) in levels.iter().enumerate().skip(i + 1) {
+            if variants.contains(&other.puzzle) {
+                duplicates.push((i, j));
+            }
+        }
+    }
+    duplicates
+}
+
 pub struct GameLevel {
     pub name: String,
     pub puzzle: Puzzle,
@@ -348,6 +983,7 @@ pub fn twin_lakes() -> GameLevel {
             ]),
             row_count: vec![2, 1, 2, 1],
             col_count: vec![2, 1, 1, 2],
+            sandbox: false,
         },
     }
 }
@@ -365,6 +1001,7 @@ pub fn forest() -> GameLevel {
             ]),
             row_count: vec![2, 1, 2, 1],
             col_count: vec![2, 1, 1, 2],
+            sandbox: false,
         },
     }
 }
@@ -382,6 +1019,7 @@ pub fn green_mountain() -> GameLevel {
             ]),
             row_count: vec![1, 1, 1, 2],
             col_count: vec![1, 2, 1, 1],
+            sandbox: false,
         },
     }
 }
@@ -399,6 +1037,7 @@ pub fn green_lake() -> GameLevel {
             ]),
             row_count: vec![1, 1, 1, 2],
             col_count: vec![1, 2, 1, 1],
+            sandbox: false,
         },
     }
 }
@@ -417,6 +1056,7 @@ pub fn mountain_lakes() -> GameLevel {
             ]),
             row_count: vec![2, 1, 1, 2, 1],
             col_count: vec![1, 1, 2, 1, 2],
+            sandbox: false,
         },
     }
 }
@@ -435,6 +1075,7 @@ pub fn twin_mountains() -> GameLevel {
             ]),
             row_count: vec![2, 1, 0, 1, 2],
             col_count: vec![2, 0, 2, 0, 2],
+            sandbox: false,
         },
     }
 }
@@ -453,6 +1094,7 @@ pub fn lonely_mountain() -> GameLevel {
             ]),
             row_count: vec![1, 2, 2, 2, 2],
             col_count: vec![2, 1, 2, 2, 2],
+            sandbox: false,
         },
     }
 }
@@ -471,6 +1113,7 @@ pub fn lake_valley() -> GameLevel {
             ]),
             row_count: vec![1, 2, 2, 1, 2],
             col_count: vec![2, 1, 2, 1, 2],
+            sandbox: false,
         },
     }
 }
@@ -485,6 +1128,7 @@ pub fn first_level() -> GameLevel {
             ]),
             row_count: vec![1],
             col_count: vec![1],
+            sandbox: false,
         },
     }
 }
@@ -500,6 +1144,7 @@ pub fn neighbors() -> GameLevel {
             ]),
             row_count: vec![1, 1],
             col_count: vec![1, 1],
+            sandbox: false,
         },
     }
 }
@@ -515,6 +1160,7 @@ pub fn meadow() -> GameLevel {
             ]),
             row_count: vec![2, 1],
             col_count: vec![1, 1, 1],
+            sandbox: false,
         },
     }
 }
@@ -530,6 +1176,7 @@ pub fn trees_level() -> GameLevel {
             ]),
             row_count: vec![2, 2],
             col_count: vec![1, 1, 1, 1],
+            sandbox: false,
         },
     }
 }
@@ -546,6 +1193,7 @@ pub fn single_lake() -> GameLevel {
             ]),
             row_count: vec![1, 1, 1],
             col_count: vec![1, 2, 0],
+            sandbox: false,
         },
     }
 }
@@ -563,6 +1211,7 @@ pub fn mountain() -> GameLevel {
             ]),
             row_count: vec![2, 0, 1, 1],
             col_count: vec![1, 1, 2, 0],
+            sandbox: false,
         },
     }
 }
@@ -580,6 +1229,7 @@ pub fn large_lake() -> GameLevel {
             ]),
             row_count: vec![1, 1, 2, 2],
             col_count: vec![2, 2, 1, 1],
+            sandbox: false,
         },
     }
 }
@@ -599,6 +1249,20 @@ pub fn village() -> GameLevel {
             ]),
             row_count: vec![2, 1, 3, 1, 3, 0],
             col_count: vec![2, 1, 2, 2, 1, 2],
+            sandbox: false,
+        },
+    }
+}
+
+// An all-grass field to play around with, not part of the level list.
+pub fn sandbox_level(rows: usize, cols: usize) -> GameLevel {
+    GameLevel {
+        name: "Sandbox".into(),
+        puzzle: Puzzle {
+            field: field_from_size(rows, cols),
+            row_count: vec![0; rows],
+            col_count: vec![0; cols],
+            sandbox: true,
         },
     }
 }
@@ -623,3 +1287,721 @@ pub fn all_levels() -> Vec<GameLevel> {
         village(),
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn puzzle(field: Vec<&str>, row_count: Vec<usize>, col_count: Vec<usize>) -> Puzzle {
+        Puzzle {
+            field: parse_field(field),
+            row_count,
+            col_count,
+            sandbox: false,
+        }
+    }
+
+    #[test]
+    fn within_budget_refuses_full_lines() {
+        let puzzle = puzzle(vec!["...", "..."], vec![1, 1], vec![1, 0, 1]);
+        let solution = parse_solution(vec!["x..", "..."]);
+        // The first row already has its house.
+        assert!(!within_budget(
+            &puzzle,
+            &solution,
+            Position { row: 0, col: 2 }
+        ));
+        // The middle column needs no houses at all.
+        assert!(!within_budget(
+            &puzzle,
+            &solution,
+            Position { row: 1, col: 1 }
+        ));
+        assert!(within_budget(
+            &puzzle,
+            &solution,
+            Position { row: 1, col: 2 }
+        ));
+    }
+
+    #[test]
+    fn within_budget_allows_anything_in_sandbox() {
+        let puzzle = sandbox_level(2, 2).puzzle;
+        let solution = parse_solution(vec!["xx", "xx"]);
+        assert!(within_budget(
+            &puzzle,
+            &solution,
+            Position { row: 0, col: 0 }
+        ));
+    }
+
+    fn hash_of(puzzle: &Puzzle) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        puzzle.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn equal_puzzles_hash_equal() {
+        let a = neighbors().puzzle;
+        let b = puzzle(vec!["..", ".."], vec![1, 1], vec![1, 1]);
+        assert_eq!(a, b);
+        assert_eq!(hash_of(&a), hash_of(&b));
+    }
+
+    #[test]
+    fn solver_counts_solutions() {
+        assert_eq!(
+            solve_up_to(&first_level().puzzle, AUDIT_SOLUTION_CAP).len(),
+            1
+        );
+        assert_eq!(
+            solve_up_to(&neighbors().puzzle, AUDIT_SOLUTION_CAP).len(),
+            2
+        );
+    }
+
+    #[test]
+    fn every_violation_has_a_description() {
+        for violation in [
+            ViolationType::AdjacentHouse,
+            ViolationType::OutOfBounds,
+            ViolationType::NotOnGrass,
+        ] {
+            assert!(!violation.description().is_empty(), "{:?}", violation);
+        }
+    }
+
+    #[test]
+    fn sandbox_validates_clean() {
+        let puzzle = sandbox_level(3, 3).puzzle;
+        for grid in [vec!["...", "...", "..."], vec!["xx.", "xx.", "..x"]] {
+            let result = validate_solution(&parse_solution(grid), &puzzle);
+            assert_eq!(result.error_count(), 0);
+            assert!(result.placement_violations.is_empty());
+            assert!(result.constraint_violations.is_empty());
+        }
+    }
+
+    #[test]
+    fn local_check_agrees_with_full_check() {
+        let puzzle = trees_level().puzzle;
+        let solution = parse_solution(vec!["x...", "...."]);
+        let has_house = house_grid(&puzzle, &solution);
+        for row in 0..puzzle.rows() {
+            for col in 0..puzzle.cols() {
+                let position = Position { row, col };
+                if puzzle.field[row][col] != CellType::Grass || has_house[row][col] {
+                    assert!(!is_placement_locally_valid(&puzzle, &has_house, position));
+                    continue;
+                }
+                let mut placed = solution.clone();
+                placed.placements.push(Placement::new(position));
+                let full = validate_solution(&placed, &puzzle);
+                assert_eq!(
+                    is_placement_locally_valid(&puzzle, &has_house, position),
+                    full.placement_violations.is_empty(),
+                    "{:?}",
+                    position
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn render_ascii_matches_source_grid() {
+        let puzzle = first_level().puzzle;
+        let solution = solve(&puzzle).unwrap();
+        assert_eq!(render_ascii(&puzzle, &solution), "x\n");
+
+        let puzzle = trees_level().puzzle;
+        let solution = parse_solution(vec!["x.Tx", ".Tx."]);
+        assert_eq!(render_ascii(&puzzle, &solution), "x.Tx\n.Tx.\n");
+    }
+
+    // Parses the grid as both field and houses and checks that rendering gives it back.
+    fn assert_roundtrip(grid: &[&str]) {
+        let field = parse_field(grid.to_vec());
+        let puzzle = Puzzle {
+            row_count: vec![0; field.len()],
+            col_count: vec![0; field[0].len()],
+            field,
+            sandbox: false,
+        };
+        let solution = parse_solution(grid.to_vec());
+        let expected: String = grid.iter().map(|line| format!("{}\n", line)).collect();
+        assert_eq!(render_ascii(&puzzle, &solution), expected);
+    }
+
+    #[test]
+    fn roundtrip_empty_field() {
+        assert_roundtrip(&["...", "..."]);
+    }
+
+    #[test]
+    fn roundtrip_houses_and_terrain() {
+        assert_roundtrip(&["x.T", ".Lx", "M.x"]);
+        assert_roundtrip(&["TTT", "TxT", "TTT"]);
+    }
+
+    #[test]
+    fn roundtrip_built_in_levels() {
+        for level in all_levels() {
+            let solution = solve(&level.puzzle).unwrap();
+            let rendered = render_ascii(&level.puzzle, &solution);
+            assert_roundtrip(&rendered.lines().collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn try_place_rejects_bad_cells() {
+        let puzzle = trees_level().puzzle;
+        assert!(matches!(
+            Placement::try_place(&puzzle, Position { row: 2, col: 0 }),
+            Err(ViolationType::OutOfBounds)
+        ));
+        assert!(matches!(
+            Placement::try_place(&puzzle, Position { row: 0, col: 4 }),
+            Err(ViolationType::OutOfBounds)
+        ));
+        assert!(matches!(
+            Placement::try_place(&puzzle, Position { row: 0, col: 2 }),
+            Err(ViolationType::NotOnGrass)
+        ));
+        let placement = Placement::try_place(&puzzle, Position { row: 1, col: 0 }).unwrap();
+        assert_eq!(placement.position, Position { row: 1, col: 0 });
+    }
+
+    #[test]
+    fn violation_lines_truncate_with_suffix() {
+        let puzzle = puzzle(vec![".....", "....."], vec![0, 0], vec![0; 5]);
+        // Five houses in a row, each next to another one.
+        let solution = parse_solution(vec!["xxxxx", "....."]);
+        let result = validate_solution(&solution, &puzzle);
+        assert_eq!(result.placement_violations.len(), 5);
+
+        let lines = result.violation_lines(2);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            "House #0: This house has another house right next to it"
+        );
+        assert_eq!(lines[2], "...and 3 more");
+        assert_eq!(result.violation_lines(5).len(), 5);
+    }
+
+    #[test]
+    fn remaining_houses_goes_negative_when_over() {
+        let puzzle = meadow().puzzle;
+        // The first row needs two houses but has one, the second needs one but has two.
+        let solution = parse_solution(vec!["x..", "x.x"]);
+        let remaining = remaining_houses(&puzzle, &solution);
+        assert_eq!(remaining.rows, vec![1, -1]);
+        assert_eq!(remaining.cols, vec![-1, 1, 0]);
+    }
+
+    fn positions(solution: &Solution) -> Vec<Position> {
+        solution
+            .placements
+            .iter()
+            .map(|placement| placement.position)
+            .collect()
+    }
+
+    #[test]
+    fn solve_is_deterministic() {
+        // Neighbors and Lake Valley have several solutions to pick from.
+        for level in [neighbors(), lake_valley()] {
+            let first = solve(&level.puzzle).unwrap();
+            let second = solve(&level.puzzle).unwrap();
+            assert_eq!(positions(&first), positions(&second), "{}", level.name);
+        }
+    }
+
+    #[test]
+    fn flipping_twice_restores_level() {
+        let level = village();
+        let mut puzzle = level.puzzle.clone();
+        let mut solution = solve(&puzzle).unwrap();
+        let original = positions(&solution);
+        puzzle.flip_horizontal(&mut solution);
+        assert_ne!(puzzle, level.puzzle);
+        puzzle.flip_horizontal(&mut solution);
+        assert_eq!(puzzle, level.puzzle);
+        assert_eq!(positions(&solution), original);
+    }
+
+    #[test]
+    fn solutions_stay_valid_under_every_transform() {
+        for level in all_levels() {
+            let mut puzzle = level.puzzle.clone();
+            let mut solution = solve(&puzzle).unwrap();
+            for _ in 0..4 {
+                puzzle.rotate_90(&mut solution);
+                assert!(
+                    validate_solution(&solution, &puzzle).complete,
+                    "{}",
+                    level.name
+                );
+                puzzle.flip_horizontal(&mut solution);
+                assert!(
+                    validate_solution(&solution, &puzzle).complete,
+                    "{}",
+                    level.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rotated_solution_stays_valid() {
+        let mut puzzle = mountain_lakes().puzzle;
+        let mut solution = solve(&puzzle).unwrap();
+        let (rows, cols) = puzzle.dims();
+        puzzle.rotate_90(&mut solution);
+        assert_eq!(puzzle.dims(), (cols, rows));
+        assert!(validate_solution(&solution, &puzzle).complete);
+    }
+
+    #[test]
+    fn auto_place_finds_single_forced_house() {
+        // The top row needs one house and has a single grass cell for it.
+        let puzzle = puzzle(vec!["T.T", "...", "..."], vec![1, 1, 1], vec![1, 1, 1]);
+        let forced = auto_place_forced(&puzzle, &Solution::default());
+        let forced = positions(&Solution { placements: forced });
+        assert_eq!(forced, vec![Position { row: 0, col: 1 }]);
+
+        // With that house in place every open line has a choice left.
+        let solution = parse_solution(vec![".x.", "...", "..."]);
+        assert!(auto_place_forced(&puzzle, &solution).is_empty());
+    }
+
+    #[test]
+    fn field_at_cell_limit_is_accepted() {
+        let field = checked_field_from_size(10, MAX_CELLS / 10).unwrap();
+        assert_eq!(field.len() * field[0].len(), MAX_CELLS);
+    }
+
+    #[test]
+    fn field_over_cell_limit_is_rejected() {
+        assert!(checked_field_from_size(10, MAX_CELLS / 10 + 1).is_err());
+        assert!(checked_field_from_size(usize::MAX, 2).is_err());
+    }
+
+    // Small xorshift generator, so the differential test needs no dependencies
+    // and every seed reproduces the same case.
+    struct Rng(u64);
+
+    impl Rng {
+        fn new(seed: u64) -> Self {
+            Rng(seed.wrapping_mul(0x9e3779b97f4a7c15) | 1)
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % n as u64) as usize
+        }
+    }
+
+    fn random_case(seed: u64) -> (Puzzle, Solution) {
+        let mut rng = Rng::new(seed);
+        let (rows, cols) = (1 + rng.below(5), 1 + rng.below(5));
+        let terrain = [
+            CellType::Grass,
+            CellType::Grass,
+            CellType::Grass,
+            CellType::Tree,
+            CellType::Lake,
+            CellType::Mountain,
+        ];
+        let field = (0..rows)
+            .map(|_| {
+                (0..cols)
+                    .map(|_| terrain[rng.below(terrain.len())])
+                    .collect()
+            })
+            .collect();
+        let puzzle = Puzzle {
+            field,
+            row_count: (0..rows).map(|_| rng.below(cols + 1)).collect(),
+            col_count: (0..cols).map(|_| rng.below(rows + 1)).collect(),
+            sandbox: false,
+        };
+        let mut solution = Solution::default();
+        for row in 0..rows {
+            for col in 0..cols {
+                if rng.below(3) == 0 {
+                    solution
+                        .placements
+                        .push(Placement::new(Position { row, col }));
+                }
+            }
+        }
+        (puzzle, solution)
+    }
+
+    fn line_status(count: usize, required: usize) -> &'static str {
+        match count.cmp(&required) {
+            std::cmp::Ordering::Less => "under",
+            std::cmp::Ordering::Equal => "match",
+            std::cmp::Ordering::Greater => "over",
+        }
+    }
+
+    // Deliberately simple restatement of the rules, comparing every house with
+    // every other house and cell instead of going through the house grid.
+    fn reference_violations(puzzle: &Puzzle, solution: &Solution) -> Vec<String> {
+        let houses = positions(solution);
+        let mut violations = Vec::new();
+        for row in 0..puzzle.rows() {
+            let count = houses.iter().filter(|house| house.row == row).count();
+            let status = line_status(count, puzzle.row_count[row]);
+            violations.push(format!("row {} {}", row, status));
+        }
+        for col in 0..puzzle.cols() {
+            let count = houses.iter().filter(|house| house.col == col).count();
+            let status = line_status(count, puzzle.col_count[col]);
+            violations.push(format!("col {} {}", col, status));
+        }
+        for (index, house) in houses.iter().enumerate() {
+            if houses
+                .iter()
+                .any(|other| house.manhattan_distance(*other) == 1)
+            {
+                violations.push(format!("house {} adjacent", index));
+            }
+        }
+        for row in 0..puzzle.rows() {
+            for col in 0..puzzle.cols() {
+                let (count, required) = match puzzle.field[row][col] {
+                    CellType::Lake => {
+                        let around = houses.iter().filter(|house| {
+                            house.row.abs_diff(row) <= 1 && house.col.abs_diff(col) <= 1
+                        });
+                        (around.count(), 3)
+                    }
+                    CellType::Mountain => {
+                        let diagonal = houses.iter().filter(|house| {
+                            house.row != row && house.row.abs_diff(row) == house.col.abs_diff(col)
+                        });
+                        (diagonal.count(), 2)
+                    }
+                    _ => continue,
+                };
+                let status = line_status(count, required);
+                violations.push(format!("cell ({}, {}) {}", row, col, status));
+            }
+        }
+        violations.sort();
+        violations
+    }
+
+    fn normalized_violations(result: &ValidationResult) -> Vec<String> {
+        let status = |status: &LineStatus| match status {
+            LineStatus::Underflow => "under",
+            LineStatus::Match => "match",
+            LineStatus::Overflow => "over",
+        };
+        let mut violations = Vec::new();
+        for (row, row_status) in result.row_status.iter().enumerate() {
+            violations.push(format!("row {} {}", row, status(row_status)));
+        }
+        for (col, col_status) in result.col_status.iter().enumerate() {
+            violations.push(format!("col {} {}", col, status(col_status)));
+        }
+        for violation in &result.placement_violations {
+            assert!(matches!(violation.violation, ViolationType::AdjacentHouse));
+            violations.push(format!("house {} adjacent", violation.house_index));
+        }
+        for violation in &result.constraint_violations {
+            let status = match violation.violation {
+                ConstraintViolationType::Underflow => "under",
+                ConstraintViolationType::Match => "match",
+                ConstraintViolationType::Overflow => "over",
+            };
+            let Position { row, col } = violation.position;
+            violations.push(format!("cell ({}, {}) {}", row, col, status));
+        }
+        violations.sort();
+        violations
+    }
+
+    // Each seed is a separate case, so a failure is reproduced by its seed alone.
+    #[test]
+    fn validate_solution_matches_reference() {
+        for seed in 0..2000 {
+            let (puzzle, solution) = random_case(seed);
+            let result = validate_solution(&solution, &puzzle);
+            let expected = reference_violations(&puzzle, &solution);
+            assert_eq!(
+                normalized_violations(&result),
+                expected,
+                "seed {}:\n{}{}",
+                seed,
+                puzzle,
+                render_ascii(&puzzle, &solution)
+            );
+            let reference_complete = expected
+                .iter()
+                .all(|violation| violation.ends_with("match"));
+            assert_eq!(result.complete, reference_complete, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn built_in_levels_are_sane_and_solvable() {
+        for level in all_levels() {
+            assert_eq!(
+                sanity_issues(&level.puzzle),
+                Vec::<String>::new(),
+                "{}",
+                level.name
+            );
+            let solutions = solve_up_to(&level.puzzle, 1);
+            assert!(!solutions.is_empty(), "{} has no solution", level.name);
+            assert!(validate_solution(&solutions[0], &level.puzzle).complete);
+        }
+    }
+
+    #[test]
+    fn built_in_levels_are_distinct() {
+        assert_eq!(find_duplicates(&all_levels()), Vec::new());
+    }
+
+    #[test]
+    fn rotated_and_flipped_copies_are_duplicates() {
+        let original = trees_level();
+        let mut rotated = original.puzzle.clone();
+        rotated.rotate_90(&mut Solution::default());
+        assert_ne!(rotated, original.puzzle);
+        let levels = vec![trees_level(), test_level("Rotated", rotated)];
+        assert_eq!(find_duplicates(&levels), vec![(0, 1)]);
+
+        let mut flipped = original.puzzle.clone();
+        flipped.flip_horizontal(&mut Solution::default());
+        assert_ne!(flipped, original.puzzle);
+        let levels = vec![original, meadow(), test_level("Flipped", flipped)];
+        assert_eq!(find_duplicates(&levels), vec![(0, 2)]);
+    }
+
+    #[test]
+    fn complete_solution_keeps_placed_houses() {
+        let puzzle = neighbors().puzzle;
+        // Neighbors has two solutions; the placed house picks one of them.
+        let solution = parse_solution(vec![".x", ".."]);
+        let completed = complete_solution(&puzzle, &solution).unwrap();
+        assert_eq!(
+            positions(&completed),
+            vec![Position { row: 0, col: 1 }, Position { row: 1, col: 0 }]
+        );
+    }
+
+    #[test]
+    fn blocking_house_leaves_no_completion() {
+        let puzzle = trees_level().puzzle;
+        assert!(complete_solution(&puzzle, &Solution::default()).is_some());
+        // The only solution leaves the top-left corner empty.
+        let solution = parse_solution(vec!["x...", "...."]);
+        assert!(complete_solution(&puzzle, &solution).is_none());
+        // Houses off the field can't be completed either.
+        let solution = parse_solution(vec!["....", "....", "x..."]);
+        assert!(complete_solution(&puzzle, &solution).is_none());
+    }
+
+    #[test]
+    fn describe_cell_reports_violating_house() {
+        let puzzle = trees_level().puzzle;
+        let solution = parse_solution(vec!["xx..", "...."]);
+        assert_eq!(
+            describe_cell(&puzzle, &solution, Position { row: 0, col: 1 }),
+            [
+                "Cell (0, 1): Grass",
+                "House #1",
+                "This house has another house right next to it",
+                "Neighbor (1, 1): Tree",
+                "Neighbor (0, 2): Tree",
+                "Neighbor (0, 0): Grass, house",
+            ]
+            .join("\n")
+        );
+    }
+
+    fn test_level(name: &str, puzzle: Puzzle) -> GameLevel {
+        GameLevel {
+            name: name.into(),
+            puzzle,
+        }
+    }
+
+    #[test]
+    fn audit_reports_each_kind_of_level() {
+        let levels = [
+            first_level(),
+            // Both houses of the row would stand next to each other.
+            test_level("Crowded", puzzle(vec![".."], vec![2], vec![1, 1])),
+            // One house in the row but none in any column.
+            test_level("Mismatched", puzzle(vec![".."], vec![1], vec![0, 0])),
+        ];
+        let audits = audit_levels(&levels);
+
+        assert_eq!(audits[0].name, "First");
+        assert_eq!(audits[0].solution_count, 1);
+        assert!(audits[0].solvable() && audits[0].unique());
+        assert!(audits[0].issues.is_empty());
+
+        assert_eq!(audits[1].solution_count, 0);
+        assert!(!audits[1].solvable() && !audits[1].unique());
+        assert!(audits[1].issues.is_empty());
+
+        assert_eq!(audits[2].solution_count, 0);
+        assert!(!audits[2].solvable());
+        assert_eq!(
+            audits[2].issues,
+            vec!["Row counts add up to 1 but column counts to 0"]
+        );
+    }
+
+    #[test]
+    fn audit_caps_solution_count() {
+        let audits = audit_levels(&[neighbors(), lake_valley()]);
+        assert_eq!(audits[0].solution_count, 2);
+        assert!(audits[0].solvable() && !audits[0].unique());
+        assert_eq!(audits[1].solution_count, AUDIT_SOLUTION_CAP);
+    }
+
+    #[test]
+    fn active_rules_follow_terrain() {
+        let lake_rule = "A lake needs exactly 3 houses around it";
+        let mountain_rule = "A mountain needs exactly 2 houses on its diagonals";
+
+        let rules = active_rules(&single_lake().puzzle);
+        assert!(rules.contains(&lake_rule));
+        assert!(!rules.contains(&mountain_rule));
+
+        let rules = active_rules(&mountain().puzzle);
+        assert!(rules.contains(&mountain_rule));
+        assert!(!rules.contains(&lake_rule));
+
+        let rules = active_rules(&meadow().puzzle);
+        assert_eq!(rules.len(), 3);
+        assert!(!rules.contains(&lake_rule) && !rules.contains(&mountain_rule));
+
+        assert_eq!(
+            active_rules(&sandbox_level(2, 2).puzzle),
+            vec!["Build anywhere on grass, there are no rules"]
+        );
+    }
+
+    #[test]
+    fn coverage_counts_houses_and_their_neighbours() {
+        let row = puzzle(vec!["..."], vec![1], vec![0, 1, 0]);
+        assert_eq!(coverage(&row, &parse_solution(vec![".x."])), 1.0);
+        assert_eq!(coverage(&row, &parse_solution(vec!["x.."])), 2.0 / 3.0);
+
+        let sparse = puzzle(vec!["...", ".T.", "..."], vec![1, 0, 0], vec![1, 0, 0]);
+        assert_eq!(
+            coverage(&sparse, &parse_solution(vec!["x..", "...", "..."])),
+            3.0 / 8.0
+        );
+        assert_eq!(coverage(&sparse, &Solution::default()), 0.0);
+
+        let no_grass = puzzle(vec!["TL"], vec![0], vec![0, 0]);
+        assert_eq!(coverage(&no_grass, &Solution::default()), 1.0);
+    }
+
+    #[test]
+    fn placeable_cells_skip_terrain_houses_and_their_neighbours() {
+        let puzzle = puzzle(vec![".T.", "..."], vec![1, 1], vec![1, 0, 1]);
+        let solution = parse_solution(vec!["...", "..x"]);
+        assert_eq!(
+            placeable_cells(&puzzle, &solution),
+            vec![vec![true, false, false], vec![true, false, false]]
+        );
+    }
+
+    #[test]
+    fn position_offsets_distances_and_bounds() {
+        let origin = Position { row: 0, col: 0 };
+        assert_eq!(origin.offset(-1, 0), None);
+        assert_eq!(origin.offset(0, -1), None);
+        assert_eq!(origin.offset(2, 3), Some(Position { row: 2, col: 3 }));
+        assert_eq!(
+            Position { row: 2, col: 3 }.offset(-1, -3),
+            Some(Position { row: 1, col: 0 })
+        );
+
+        assert_eq!(origin.manhattan_distance(origin), 0);
+        assert_eq!(
+            Position { row: 1, col: 4 }.manhattan_distance(Position { row: 3, col: 2 }),
+            4
+        );
+
+        assert!(Position { row: 2, col: 3 }.in_bounds(3, 4));
+        assert!(!Position { row: 3, col: 3 }.in_bounds(3, 4));
+        assert!(!Position { row: 2, col: 4 }.in_bounds(3, 4));
+    }
+
+    #[test]
+    fn puzzle_ids_are_stable_and_follow_the_counts() {
+        // Saved progress is keyed by this id, so it must never change between builds.
+        assert_eq!(first_level().puzzle.id(), 4353071324181915873);
+
+        let original = puzzle(vec!["...", "..."], vec![1, 1], vec![1, 0, 1]);
+        let mut changed = original.clone();
+        changed.col_count = vec![1, 1, 0];
+        assert_ne!(original.id(), changed.id());
+    }
+
+    #[test]
+    fn error_count_adds_broken_lines_and_adjacent_houses() {
+        let puzzle = puzzle(vec!["...", "..."], vec![1, 1], vec![1, 0, 1]);
+        let validation = validate_solution(&parse_solution(vec!["xx.", "..."]), &puzzle);
+        // Row 0 and column 1 overflow, row 1 and column 2 underflow, and both
+        // houses touch each other.
+        assert_eq!(validation.error_count(), 6);
+        assert!(!validation.complete);
+    }
+
+    #[test]
+    fn sanity_issues_name_each_authoring_mistake() {
+        let crowded = puzzle(vec!["..TT", "..TT"], vec![5, 5], vec![5, 5, 0, 0]);
+        assert_eq!(
+            sanity_issues(&crowded),
+            vec![
+                "10 houses required but only 4 grass cells",
+                "Row 0 needs more houses than it has grass",
+                "Row 1 needs more houses than it has grass",
+                "Column 0 needs more houses than it has grass",
+                "Column 1 needs more houses than it has grass",
+            ]
+        );
+
+        let mismatched = puzzle(vec![".."], vec![1], vec![0, 0]);
+        assert_eq!(
+            sanity_issues(&mismatched),
+            vec!["Row counts add up to 1 but column counts to 0"]
+        );
+
+        let bare_row = puzzle(vec!["..", "TT"], vec![0, 2], vec![1, 1]);
+        assert_eq!(
+            sanity_issues(&bare_row),
+            vec!["Row 1 needs more houses than it has grass"]
+        );
+        let bare_col = puzzle(vec![".T", ".T"], vec![1, 1], vec![1, 1]);
+        assert_eq!(
+            sanity_issues(&bare_col),
+            vec!["Column 1 needs more houses than it has grass"]
+        );
+
+        let short = puzzle(vec![".."], vec![1], vec![1]);
+        assert_eq!(
+            sanity_issues(&short),
+            vec!["1 row and 1 column counts for a 1x2 field"]
+        );
+        assert_eq!(sanity_issues(&first_level().puzzle), Vec::<String>::new());
+    }
+}
@@ -1,18 +1,24 @@
 use bevy::audio::PlaybackMode;
 use bevy::prelude::*;
 use bevy::window::{close_on_esc, WindowMode};
+use std::collections::{HashMap, HashSet};
 
 use self::game_screen::GameScreenPlugin;
 use self::input::GameInputPlugin;
 use self::level::Solution;
 use self::main_menu_screen::MainMenuScreenPlugin;
 use self::select_level_screen::SelectLevelScreenPlugin;
+use self::transition::ScreenTransitionPlugin;
+use self::tutorial::TutorialPlugin;
 
+mod animation;
 mod game_screen;
 mod input;
 mod level;
 mod main_menu_screen;
 mod select_level_screen;
+mod transition;
+mod tutorial;
 
 pub const TEXT_FONT_NAME: &str = "NanumBrushScript-Regular.ttf";
 pub const CUSTOM_ORANGE: Color = Color::rgb(238.0 / 256.0, 145.0 / 256.0, 2.0 / 256.0);
@@ -25,6 +31,21 @@ pub struct GameState {
     name: String,
     current_level: usize,
     hints: Vec<Vec<bool>>,
+    // Refuse placements that would exceed the row or column house count.
+    strict_budget: bool,
+    // Tint the cells where a house could be placed.
+    show_placeable: bool,
+    // Label each cell with its row and column, for debugging levels.
+    show_coordinates: bool,
+    // How many misplaced houses the status text lists before summing up the rest,
+    // usize::MAX to list all of them.
+    max_violations_shown: usize,
+    // Set when the level is solved, so stray clicks can't undo the win.
+    locked: bool,
+    // Set once the solution reveal is used, so the level no longer counts as solved.
+    revealed: bool,
+    // Puzzle::id of the levels solved in this session.
+    solved_levels: HashSet<u64>,
 }
 
 impl GameState {
@@ -37,8 +58,70 @@ impl GameState {
             name: game_level.name,
             current_level,
             hints: vec![vec![false; cols]; rows],
+            strict_budget: false,
+            show_placeable: false,
+            show_coordinates: false,
+            max_violations_shown: game_screen::FIRST_VIOLATIONS_SHOWN,
+            locked: false,
+            revealed: false,
+            solved_levels: HashSet::new(),
         }
     }
+
+    // Replaces the puzzle and clears the board, keeping the session progress.
+    pub fn load_level(&mut self, game_level: level::GameLevel) {
+        self.puzzle = game_level.puzzle;
+        self.name = game_level.name;
+        self.revealed = false;
+        self.reset_board();
+    }
+
+    // Removes every house but keeps the marks, to retry from the same deductions.
+    pub fn clear_houses(&mut self) {
+        self.solution = Solution::default();
+        self.locked = false;
+    }
+
+    // Returns the board to how the level started, marks included.
+    pub fn reset_board(&mut self) {
+        let (rows, cols) = self.puzzle.dims();
+        self.clear_houses();
+        self.hints = vec![vec![false; cols]; rows];
+    }
+
+    pub fn is_solved(&self) -> bool {
+        level::validate_solution(&self.solution, &self.puzzle).complete
+    }
+
+    // Removes the house at the position, returning whether there was one.
+    pub fn remove_house(&mut self, position: level::Position) -> bool {
+        let Some(index) = self
+            .solution
+            .placements
+            .iter()
+            .position(|placement| placement.position == position)
+        else {
+            return false;
+        };
+        self.solution.placements.remove(index);
+        self.hints[position.row][position.col] = false;
+        true
+    }
+}
+
+// Memoizes solver results per puzzle, so repeated queries skip the search.
+#[derive(Resource, Default)]
+pub struct SolverCache {
+    solutions: HashMap<level::Puzzle, Option<Solution>>,
+}
+
+impl SolverCache {
+    pub fn solve(&mut self, puzzle: &level::Puzzle) -> Option<&Solution> {
+        self.solutions
+            .entry(puzzle.clone())
+            .or_insert_with(|| level::solve(puzzle))
+            .as_ref()
+    }
 }
 
 #[derive(Resource)]
@@ -101,6 +184,42 @@ fn setup(mut commands: Commands, server: Res<AssetServer>) {
     });
 
     commands.insert_resource(GlobalVolumeSettings { volume: 0.5 });
+    commands.insert_resource(SolverCache::default());
+}
+
+// Warns about levels that can't be solved or have several solutions.
+fn log_level_audit() {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+    let levels = level::all_levels();
+    for (i, j) in level::find_duplicates(&levels) {
+        warn!(
+            "Levels \"{}\" and \"{}\" are the same puzzle",
+            levels[i].name, levels[j].name
+        );
+    }
+    for audit in level::audit_levels(&levels) {
+        for issue in &audit.issues {
+            warn!("Level \"{}\": {}", audit.name, issue);
+        }
+        if !audit.issues.is_empty() {
+            continue;
+        }
+        if !audit.solvable() {
+            warn!("Level \"{}\" has no solution", audit.name);
+        } else if !audit.unique() {
+            let at_least = if audit.solution_count == level::AUDIT_SOLUTION_CAP {
+                "at least "
+            } else {
+                ""
+            };
+            warn!(
+                "Level \"{}\" has {}{} solutions",
+                audit.name, at_least, audit.solution_count
+            );
+        }
+    }
 }
 
 fn update_sounds(
@@ -114,10 +233,7 @@ fn update_sounds(
 
 fn switch_levels(mut game_state: ResMut<GameState>, mut app_state: ResMut<NextState<AppState>>) {
     let game_level = level::all_levels().swap_remove(game_state.current_level);
-    game_state.puzzle = game_level.puzzle;
-    game_state.solution = Solution::default();
-    game_state.name = game_level.name;
-    game_state.hints = vec![vec![false; game_state.puzzle.cols()]; game_state.puzzle.rows()];
+    game_state.load_level(game_level);
     app_state.set(AppState::InGame);
 }
 
@@ -137,13 +253,89 @@ fn main() {
             ..default()
         }))
         .add_state::<AppState>()
-        .add_systems(Startup, setup)
+        .add_systems(Startup, (setup, log_level_audit))
         .add_systems(Update, close_on_esc)
         .add_systems(Update, update_sounds)
         .add_systems(OnEnter(AppState::SwitchLevel), switch_levels)
         .add_plugins(MainMenuScreenPlugin(AppState::MainMenuScreen))
         .add_plugins(SelectLevelScreenPlugin(AppState::SelectLevelScreen))
         .add_plugins(GameScreenPlugin(AppState::InGame))
+        .add_plugins(TutorialPlugin(AppState::InGame))
         .add_plugins(GameInputPlugin)
+        .add_plugins(ScreenTransitionPlugin)
         .run();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solver_cache_memoizes_solutions() {
+        let puzzle = level::first_level().puzzle;
+        let mut cache = SolverCache::default();
+        let first = cache
+            .solve(&puzzle)
+            .map(|solution| solution as *const Solution);
+        let second = cache
+            .solve(&puzzle)
+            .map(|solution| solution as *const Solution);
+        assert!(first.is_some());
+        // The second call hands back the stored entry instead of solving again.
+        assert_eq!(first, second);
+        assert_eq!(cache.solutions.len(), 1);
+    }
+
+    #[test]
+    fn remove_house_only_removes_placed_houses() {
+        let mut game_state = GameState::new(level::neighbors(), 0);
+        let house = level::Position { row: 0, col: 1 };
+        game_state
+            .solution
+            .placements
+            .push(level::Placement::new(house));
+
+        assert!(!game_state.remove_house(level::Position { row: 1, col: 0 }));
+        assert_eq!(game_state.solution.placements.len(), 1);
+
+        assert!(game_state.remove_house(house));
+        assert!(game_state.solution.placements.is_empty());
+        assert!(!game_state.remove_house(house));
+    }
+
+    #[test]
+    fn first_level_is_solved_by_its_only_house() {
+        let mut game_state = GameState::new(level::first_level(), 0);
+        assert!(!game_state.is_solved());
+        game_state
+            .solution
+            .placements
+            .push(level::Placement::new(level::Position { row: 0, col: 0 }));
+        assert!(game_state.is_solved());
+    }
+
+    #[test]
+    fn clearing_keeps_marks_and_resetting_drops_them() {
+        let mut game_state = GameState::new(level::neighbors(), 0);
+        let place = |game_state: &mut GameState| {
+            game_state
+                .solution
+                .placements
+                .push(level::Placement::new(level::Position { row: 0, col: 1 }));
+            game_state.hints[1][0] = true;
+            game_state.locked = true;
+        };
+
+        place(&mut game_state);
+        game_state.clear_houses();
+        assert!(game_state.solution.placements.is_empty());
+        assert!(game_state.hints[1][0]);
+        assert!(!game_state.locked);
+
+        place(&mut game_state);
+        game_state.reset_board();
+        assert!(game_state.solution.placements.is_empty());
+        assert!(!game_state.hints[1][0]);
+        assert!(!game_state.locked);
+    }
+}
@@ -1,7 +1,9 @@
 use bevy::app::AppExit;
 use bevy::prelude::*;
 
-use crate::AppState;
+use crate::level::sandbox_level;
+use crate::transition::ScreenTransition;
+use crate::{AppState, GameState};
 
 pub struct MainMenuScreenPlugin<S: States + Copy>(pub S);
 
@@ -42,6 +44,7 @@ struct OnMainMenuScreen;
 enum MenuButtonAction {
     Play,
     Levels,
+    Sandbox,
     Quit,
 }
 
@@ -100,9 +103,10 @@ fn create_main_menu_screen(mut commands: Commands, server: Res<AssetServer>) {
                         }),
                     );
 
-                    // Display three buttons for each action available from the main menu:
+                    // Display four buttons for each action available from the main menu:
                     // - Play
                     // - Levels
+                    // - Sandbox
                     // - Quit
                     parent.spawn((
                         ButtonBundle {
@@ -122,6 +126,26 @@ fn create_main_menu_screen(mut commands: Commands, server: Res<AssetServer>) {
                         },
                         MenuButtonAction::Levels,
                     ));
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                style: button_style.clone(),
+                                background_color: NORMAL_BUTTON.into(),
+                                image: UiImage::new(server.load("UI/button_empty.png")),
+                                ..default()
+                            },
+                            MenuButtonAction::Sandbox,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section(
+                                "sandbox",
+                                TextStyle {
+                                    font: server.load(crate::TEXT_FONT_NAME),
+                                    font_size: 56.0,
+                                    color: Color::WHITE,
+                                },
+                            ));
+                        });
                     parent.spawn((
                         ButtonBundle {
                             style: button_style.clone(),
@@ -149,7 +173,8 @@ fn button_system(
         (&Interaction, &mut BackgroundColor, &MenuButtonAction),
         (Changed<Interaction>, With<Button>),
     >,
-    mut app_state: ResMut<NextState<AppState>>,
+    mut transition: ResMut<ScreenTransition>,
+    mut game_state: ResMut<GameState>,
     mut exit: EventWriter<AppExit>,
 ) {
     for (interaction, mut color, action) in &mut interaction_query {
@@ -162,10 +187,14 @@ fn button_system(
         if *interaction == Interaction::Pressed {
             match *action {
                 MenuButtonAction::Play => {
-                    app_state.set(AppState::SwitchLevel);
+                    transition.start(AppState::SwitchLevel);
                 }
                 MenuButtonAction::Levels => {
-                    app_state.set(AppState::SelectLevelScreen);
+                    transition.start(AppState::SelectLevelScreen);
+                }
+                MenuButtonAction::Sandbox => {
+                    game_state.load_level(sandbox_level(5, 5));
+                    transition.start(AppState::InGame);
                 }
                 MenuButtonAction::Quit => {
                     exit.send(AppExit);
@@ -5,6 +5,7 @@ use bevy::prelude::*;
 use bevy::ui::{Style, UiRect, Val};
 
 use crate::level::{all_levels, GameLevel};
+use crate::transition::ScreenTransition;
 use crate::{AppState, GameState};
 
 pub struct SelectLevelScreenPlugin<S: States + Copy>(pub S);
@@ -18,13 +19,19 @@ impl<S: States + Copy> Plugin for SelectLevelScreenPlugin<S> {
 }
 
 const BUTTON_COLOR: Color = Color::rgb(239.0 / 256.0, 167.0 / 256.0, 115.0 / 256.0);
+const SOLVED_BUTTON_COLOR: Color = Color::rgb(140.0 / 256.0, 190.0 / 256.0, 100.0 / 256.0);
 
 #[derive(Resource)]
 pub struct SelectLevelScreenRoot {
     root: Entity,
 }
 
-pub fn create_select_level_screen(mut commands: Commands, server: Res<AssetServer>) {
+// All levels stay unlocked, solved ones are shown in green.
+pub fn create_select_level_screen(
+    mut commands: Commands,
+    server: Res<AssetServer>,
+    game_state: Res<GameState>,
+) {
     let id = commands
         .spawn(ImageBundle {
             style: Style {
@@ -80,7 +87,14 @@ pub fn create_select_level_screen(mut commands: Commands, server: Res<AssetServe
                 })
                 .with_children(|builder| {
                     for (index, level) in all_levels().iter().enumerate() {
-                        item_level(builder, index, level, server.load(crate::TEXT_FONT_NAME));
+                        let solved = game_state.solved_levels.contains(&level.puzzle.id());
+                        item_level(
+                            builder,
+                            index,
+                            level,
+                            solved,
+                            server.load(crate::TEXT_FONT_NAME),
+                        );
                     }
                 });
         })
@@ -94,7 +108,13 @@ pub struct LevelIndex {
     index: usize,
 }
 
-fn item_level(builder: &mut ChildBuilder, index: usize, level: &GameLevel, font: Handle<Font>) {
+fn item_level(
+    builder: &mut ChildBuilder,
+    index: usize,
+    level: &GameLevel,
+    solved: bool,
+    font: Handle<Font>,
+) {
     builder
         .spawn((
             ButtonBundle {
@@ -105,7 +125,11 @@ fn item_level(builder: &mut ChildBuilder, index: usize, level: &GameLevel, font:
                     padding: UiRect::all(Val::Px(3.0)),
                     ..default()
                 },
-                background_color: BackgroundColor(BUTTON_COLOR),
+                background_color: BackgroundColor(if solved {
+                    SOLVED_BUTTON_COLOR
+                } else {
+                    BUTTON_COLOR
+                }),
                 border_color: BorderColor(Color::BLACK),
                 ..default()
             },
@@ -125,7 +149,7 @@ fn item_level(builder: &mut ChildBuilder, index: usize, level: &GameLevel, font:
 
 pub fn handle_button_click(
     mut interaction_query: Query<(&Interaction, &LevelIndex), Changed<Interaction>>,
-    mut app_state: ResMut<NextState<AppState>>,
+    mut transition: ResMut<ScreenTransition>,
     mut game_state: ResMut<GameState>,
 ) {
     for (interaction, level_index) in &mut interaction_query {
@@ -133,7 +157,7 @@ pub fn handle_button_click(
         match *interaction {
             Interaction::Pressed => {
                 game_state.current_level = level_index.index;
-                app_state.set(AppState::SwitchLevel);
+                transition.start(AppState::SwitchLevel);
             }
             Interaction::Hovered => {}
             Interaction::None => {}
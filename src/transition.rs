@@ -0,0 +1,164 @@
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+use std::time::Duration;
+
+use crate::animation::ease_in_out;
+use crate::input::{KeyAction, KeyBindings};
+use crate::AppState;
+
+// Total length of a fade: half darkening, half brightening again.
+pub const SCREEN_TRANSITION_SECONDS: f32 = 0.4;
+
+pub struct ScreenTransitionPlugin;
+
+impl Plugin for ScreenTransitionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScreenTransition>()
+            .add_systems(Startup, create_transition_overlay)
+            .add_systems(Update, update_screen_transition);
+    }
+}
+
+// Fades the screen to black, switches the app state in the dark and fades back in.
+#[derive(Resource)]
+pub struct ScreenTransition {
+    target: Option<AppState>,
+    // Whether the state has been switched, i.e. the fade is past its midpoint.
+    switched: bool,
+    timer: Timer,
+}
+
+impl Default for ScreenTransition {
+    fn default() -> Self {
+        Self {
+            target: None,
+            switched: false,
+            timer: Timer::from_seconds(SCREEN_TRANSITION_SECONDS, TimerMode::Once),
+        }
+    }
+}
+
+impl ScreenTransition {
+    pub fn start(&mut self, to: AppState) {
+        // Retarget a fade that is still darkening instead of restarting it.
+        if self.target.is_some() && !self.switched {
+            self.target = Some(to);
+            return;
+        }
+        self.target = Some(to);
+        self.switched = false;
+        self.timer.reset();
+    }
+
+    // Whether a fade is still running, including its brightening half.
+    pub fn is_active(&self) -> bool {
+        self.target.is_some()
+    }
+
+    // Runs the fade for the elapsed time, returning the state to switch to once it
+    // reaches its midpoint. Skipping switches right away and ends the fade.
+    pub fn advance(&mut self, delta: Duration, skip: bool) -> Option<AppState> {
+        let target = self.target?;
+        self.timer.tick(delta);
+        let mut switch_to = None;
+        if !self.switched && (skip || self.timer.percent() >= 0.5) {
+            switch_to = Some(target);
+            self.switched = true;
+        }
+        if skip || self.timer.finished() {
+            self.target = None;
+        }
+        switch_to
+    }
+
+    // Opacity of the black overlay, darkest at the midpoint of the fade.
+    pub fn alpha(&self) -> f32 {
+        if !self.is_active() {
+            return 0.0;
+        }
+        ease_in_out(1.0 - (2.0 * self.timer.percent() - 1.0).abs())
+    }
+}
+
+#[derive(Component)]
+struct TransitionOverlay;
+
+fn create_transition_overlay(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            background_color: Color::NONE.into(),
+            focus_policy: FocusPolicy::Pass,
+            z_index: ZIndex::Global(100),
+            ..default()
+        },
+        TransitionOverlay,
+    ));
+}
+
+fn update_screen_transition(
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut transition: ResMut<ScreenTransition>,
+    mut app_state: ResMut<NextState<AppState>>,
+    mut overlay_query: Query<&mut BackgroundColor, With<TransitionOverlay>>,
+) {
+    if !transition.is_active() {
+        return;
+    }
+    let skip = bindings.just_pressed(&keys, KeyAction::SkipTransition);
+    if let Some(target) = transition.advance(time.delta(), skip) {
+        app_state.set(target);
+    }
+    overlay_query.single_mut().0 = Color::BLACK.with_a(transition.alpha());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transition_is_active_once_started() {
+        let mut transition = ScreenTransition::default();
+        assert!(!transition.is_active());
+        transition.start(AppState::SwitchLevel);
+        assert!(transition.is_active());
+    }
+
+    #[test]
+    fn state_switches_at_the_midpoint() {
+        let mut transition = ScreenTransition::default();
+        transition.start(AppState::SwitchLevel);
+        let quarter = Duration::from_secs_f32(SCREEN_TRANSITION_SECONDS * 0.25);
+
+        assert_eq!(transition.advance(quarter, false), None);
+        assert_eq!(
+            transition.advance(quarter, false),
+            Some(AppState::SwitchLevel)
+        );
+        assert_eq!(transition.advance(quarter, false), None);
+        assert!(transition.is_active());
+
+        // The rest of the fade, with room for rounding in the quarters.
+        assert_eq!(transition.advance(quarter * 2, false), None);
+        assert!(!transition.is_active());
+        assert_eq!(transition.alpha(), 0.0);
+    }
+
+    #[test]
+    fn skipping_switches_and_finishes_at_once() {
+        let mut transition = ScreenTransition::default();
+        transition.start(AppState::SwitchLevel);
+        assert_eq!(
+            transition.advance(Duration::ZERO, true),
+            Some(AppState::SwitchLevel)
+        );
+        assert!(!transition.is_active());
+    }
+}
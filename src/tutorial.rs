@@ -0,0 +1,167 @@
+use bevy::prelude::*;
+
+use crate::game_screen::HUD_HEIGHT;
+use crate::GameState;
+
+pub struct TutorialPlugin<S: States + Copy>(pub S);
+
+impl<S: States + Copy> Plugin for TutorialPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(self.0), create_tutorial)
+            .add_systems(Update, update_tutorial.run_if(in_state(self.0)))
+            .add_systems(OnExit(self.0), destroy_tutorial);
+    }
+}
+
+// A prompt shown on a level until its predicate passes. Steps name their level
+// rather than index it, so reordering the levels doesn't move the prompts.
+pub struct TutorialStep {
+    pub level: &'static str,
+    pub message: &'static str,
+    pub done: fn(&GameState) -> bool,
+}
+
+fn has_house(game_state: &GameState) -> bool {
+    !game_state.solution.placements.is_empty()
+}
+
+fn has_mark(game_state: &GameState) -> bool {
+    game_state.hints.iter().flatten().any(|&hint| hint)
+}
+
+// The steps are shown in order; the first unfinished one for the current level is displayed.
+pub fn tutorial_steps() -> Vec<TutorialStep> {
+    vec![
+        TutorialStep {
+            level: "First",
+            message: "Click on the grass to build a house",
+            done: has_house,
+        },
+        TutorialStep {
+            level: "First",
+            message: "Each row and column needs as many houses as its number",
+            done: GameState::is_solved,
+        },
+        TutorialStep {
+            level: "Neighbors",
+            message: "Right-click a cell to mark where a house can't go",
+            done: |game_state| has_mark(game_state) || game_state.is_solved(),
+        },
+        TutorialStep {
+            level: "Neighbors",
+            message: "Houses can't be next to each other, diagonals are fine",
+            done: GameState::is_solved,
+        },
+        TutorialStep {
+            level: "Lake",
+            message: "A lake needs exactly 3 houses in the 8 cells around it",
+            done: GameState::is_solved,
+        },
+        TutorialStep {
+            level: "Mountain",
+            message: "A mountain needs exactly 2 houses on its diagonals",
+            done: GameState::is_solved,
+        },
+    ]
+}
+
+pub fn current_tutorial_message(game_state: &GameState) -> Option<&'static str> {
+    if game_state.puzzle.sandbox {
+        return None;
+    }
+    tutorial_steps()
+        .into_iter()
+        .filter(|step| step.level == game_state.name)
+        .find(|step| !(step.done)(game_state))
+        .map(|step| step.message)
+}
+
+#[derive(Component)]
+struct OnTutorial;
+
+#[derive(Component)]
+struct TutorialText;
+
+fn create_tutorial(mut commands: Commands, server: Res<AssetServer>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    top: Val::Px(HUD_HEIGHT),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            OnTutorial,
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: server.load(crate::TEXT_FONT_NAME),
+                        font_size: 48.0,
+                        color: crate::CUSTOM_ORANGE,
+                    },
+                ),
+                TutorialText,
+            ));
+        });
+}
+
+fn update_tutorial(
+    game_state: Res<GameState>,
+    mut text_query: Query<&mut Text, With<TutorialText>>,
+    new_text_query: Query<(), Added<TutorialText>>,
+) {
+    if !game_state.is_changed() && new_text_query.is_empty() {
+        return;
+    }
+    let mut text = text_query.single_mut();
+    text.sections[0].value = current_tutorial_message(&game_state)
+        .unwrap_or_default()
+        .to_string();
+}
+
+fn destroy_tutorial(mut commands: Commands, query: Query<Entity, With<OnTutorial>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::{all_levels, neighbors, parse_solution};
+
+    #[test]
+    fn steps_name_existing_levels() {
+        let names: Vec<String> = all_levels().into_iter().map(|level| level.name).collect();
+        for step in tutorial_steps() {
+            assert!(
+                names.iter().any(|name| name == step.level),
+                "{}",
+                step.level
+            );
+        }
+    }
+
+    #[test]
+    fn steps_advance_as_predicates_pass() {
+        let mut game_state = GameState::new(neighbors(), 1);
+        assert_eq!(
+            current_tutorial_message(&game_state),
+            Some("Right-click a cell to mark where a house can't go")
+        );
+        game_state.hints[0][1] = true;
+        assert_eq!(
+            current_tutorial_message(&game_state),
+            Some("Houses can't be next to each other, diagonals are fine")
+        );
+        game_state.solution = parse_solution(vec!["x.", ".x"]);
+        assert_eq!(current_tutorial_message(&game_state), None);
+    }
+}